@@ -23,6 +23,10 @@ impl Error {
             backtrace: backtrace::Backtrace::new(),
         }
     }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
 }
 
 impl<E> From<E> for Error
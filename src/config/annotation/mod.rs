@@ -1,44 +1,298 @@
 // SPDX-License-Identifier: MPL-2.0
 mod params;
+mod settings;
+mod token;
+
+use std::collections::HashMap;
 
-use once_cell::sync::Lazy;
 use regex::Regex;
 
+use super::language::Syntax;
+use super::rule::Rules;
+use crate::diagnostic::{Code, Diagnostic, Span};
 use crate::{model, prelude::*};
-pub use params::Params;
+pub use params::{Params, Value};
+pub use settings::Settings;
 
 #[derive(Debug, PartialEq)]
 pub struct Annotation {
-    pub command: model::Command,
+    // `None` for the bare `END` and `ELSE` markers.
+    pub command: Option<model::Command>,
     pub target: model::Target,
+    pub meta: model::Meta,
+}
+
+#[derive(Debug, Clone)]
+pub struct Parser {
+    syntax: Syntax,
+    settings: Settings,
+    re: Regex,
+    trailing: Regex,
+    // The rules annotations may reference; `None` accepts any rule.
+    rules: Option<Rules>,
+    groups: HashMap<String, model::Group>,
 }
 
-static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*// torin ").expect("Invalid regex"));
-impl Annotation {
-    pub fn is_match(line: &str) -> bool {
-        RE.is_match(line)
+impl Parser {
+    pub fn new(syntax: &Syntax, settings: &Settings) -> Result<Self> {
+        if settings.marker.trim().is_empty() || settings.marker.contains(char::is_whitespace) {
+            return trace!("Invalid annotation marker: `{}`", settings.marker);
+        }
+        let opening = format!(
+            "{} {} ",
+            regex::escape(&syntax.prefix),
+            regex::escape(&settings.marker)
+        );
+        let re = Regex::new(&format!(r"^\s*{opening}"))?;
+        // Only LINE annotations may follow code on the same line.
+        let line_spellings = settings
+            .spellings("LINE")
+            .map(regex::escape)
+            .collect::<Vec<_>>()
+            .join("|");
+        let trailing = Regex::new(&format!(r"\s({opening})\S+ (?:{line_spellings})(?:\s|$)"))?;
+        Ok(Self {
+            syntax: syntax.clone(),
+            settings: settings.clone(),
+            re,
+            trailing,
+            rules: None,
+            groups: HashMap::new(),
+        })
+    }
+
+    pub fn rules(mut self, rules: Rules) -> Self {
+        self.rules = Some(rules);
+        self
+    }
+
+    pub fn groups(mut self, groups: HashMap<String, model::Group>) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    pub fn syntax(&self) -> &Syntax {
+        &self.syntax
+    }
+
+    pub fn is_match(&self, line: &str) -> bool {
+        self.re.is_match(line) || self.trailing_start(line).is_some()
+    }
+
+    // Whether `line` is an annotation on its own, not code with a trailing `LINE`.
+    pub fn is_standalone(&self, line: &str) -> bool {
+        self.re.is_match(line)
+    }
+
+    // Where the body of a trailing `LINE` annotation starts. The annotation must be
+    // the comment of the line, so a marker inside a string literal is ignored.
+    fn trailing_start(&self, line: &str) -> Option<usize> {
+        let comment = self.syntax.comment_start(line)?;
+        self.trailing
+            .captures_iter(line)
+            .filter_map(|caps| caps.get(1))
+            .find(|opening| opening.start() == comment)
+            .map(|opening| opening.end())
     }
 
-    pub fn parse(line: &str) -> Result<Self> {
-        if !Self::is_match(line) {
-            return trace!("Invalid line");
+    fn body_start(&self, line: &str) -> Option<usize> {
+        match self.re.find(line) {
+            Some(matched) => Some(matched.end()),
+            None => self.trailing_start(line),
         }
+    }
 
-        let ([command, target_mode], rest) = line
-            .trim()
-            .trim_start_matches("// torin ")
-            .least_sized_split::<2>(" ")?;
-        let params = Params::try_from(rest)?;
+    pub fn parse(&self, line: &str) -> std::result::Result<Annotation, Diagnostic> {
+        let indent = line.len() - line.trim_start().len();
+        let Some(body_start) = self.body_start(line) else {
+            return Err(Diagnostic::new(
+                Code::IncompleteAnnotation,
+                Span::new(indent, line.len()),
+                "not an annotation",
+            ));
+        };
 
-        Ok(Self {
-            command: command.try_into()?,
-            target: model::Target::new(target_mode, params.try_into()?)?,
+        let end = match &self.syntax.suffix {
+            Some(suffix) => {
+                let trimmed = line.trim_end();
+                trimmed
+                    .strip_suffix(suffix.as_str())
+                    .unwrap_or(trimmed)
+                    .len()
+            }
+            None => line.len(),
+        }
+        .max(body_start);
+        let mut tokens = token::tokenize(line, body_start, end).into_iter();
+        let incomplete = || {
+            Diagnostic::new(
+                Code::IncompleteAnnotation,
+                Span::new(indent, end),
+                "expected `<COMMAND> <TARGET>` after the marker",
+            )
+        };
+        let first = tokens.next().ok_or_else(incomplete)?;
+        let (command, target) = match self.settings.keyword(first.text) {
+            "END" | "ELSE" => (None, first),
+            keyword => {
+                let target = tokens.next().ok_or_else(incomplete)?;
+                let command = model::Command::try_from(keyword).map_err(|_| {
+                    Diagnostic::new(
+                        Code::UnknownCommand,
+                        first.span,
+                        format!("unknown command `{}`", first.text),
+                    )
+                    .suggest(
+                        first.text,
+                        model::Command::KEYWORDS,
+                        str::to_string,
+                    )
+                })?;
+                (Some(command), target)
+            }
+        };
+
+        let target_mode = self.settings.keyword(target.text);
+        if !model::Target::KEYWORDS.contains(&target_mode) {
+            return Err(Diagnostic::new(
+                Code::UnknownTarget,
+                target.span,
+                format!("unknown target `{}`", target.text),
+            )
+            .suggest(target.text, model::Target::KEYWORDS, str::to_string));
+        }
+
+        let params = Params::try_from(tokens.collect::<Vec<_>>())?;
+        let mut meta = model::Meta::from(&params);
+        let id = match &params.id {
+            Some(id) if !matches!(target_mode, "BEGIN" | "END" | "ELSE") => {
+                return Err(Diagnostic::new(
+                    Code::UnexpectedParameter,
+                    id.span,
+                    "`id` is only allowed on BEGIN, ELSE and END",
+                ));
+            }
+            id => id.as_ref().map(|id| id.text.clone()),
+        };
+        let count = match &params.n {
+            Some(n) if !matches!(target_mode, "NEXT" | "PREV") => {
+                return Err(Diagnostic::new(
+                    Code::UnexpectedParameter,
+                    n.span,
+                    "`n` is only allowed on NEXT and PREV",
+                ));
+            }
+            Some(n) => match n.text.parse::<usize>() {
+                Ok(count) if count > 0 => Some(count),
+                _ => {
+                    return Err(Diagnostic::new(
+                        Code::MalformedParameter,
+                        n.span,
+                        format!("`n` must be a positive integer, found `{}`", n.text),
+                    ));
+                }
+            },
+            None => None,
+        };
+        let trigger = match command {
+            Some(model::Command::Require) => {
+                let triggers = params.triggers().into_iter().map(|(_, value)| value);
+                let mut given = triggers
+                    .chain(params.when.as_ref())
+                    .chain(params.group.as_ref());
+                if let Some(param) = given.next() {
+                    return Err(Diagnostic::new(
+                        Code::InvalidRequirement,
+                        param.span,
+                        "REQUIRE blocks are always enforced and take no trigger",
+                    ));
+                }
+                match target_mode {
+                    "BEGIN" if id.is_none() => {
+                        return Err(Diagnostic::new(
+                            Code::InvalidRequirement,
+                            target.span,
+                            "REQUIRE blocks need an `id`",
+                        )
+                        .help("name the block, e.g. `REQUIRE BEGIN id=audit_log`"));
+                    }
+                    "BEGIN" => Some(model::Trigger::Always),
+                    "END" => None,
+                    _ => {
+                        return Err(Diagnostic::new(
+                            Code::InvalidRequirement,
+                            target.span,
+                            "REQUIRE only supports BEGIN and END",
+                        ));
+                    }
+                }
+            }
+            _ => match &params.group {
+                // Group triggers are checked against the declared rules on load.
+                Some(value) => {
+                    let group = crate::convert::group(&params, value, &self.groups)?;
+                    meta = meta.or(&group.meta);
+                    Some(group.trigger.clone())
+                }
+                None => {
+                    let sources = [params.rule.clone(), params.when.clone()];
+                    let trigger: Option<model::Trigger> = params.try_into()?;
+                    if let (Some(trigger), Some(declared)) = (&trigger, &self.rules) {
+                        for rule in trigger.rules() {
+                            if declared.find(rule.name()).is_none() {
+                                return Err(unknown_rule(rule.name(), &sources, declared));
+                            }
+                        }
+                    }
+                    trigger
+                }
+            },
+        };
+        let target = model::Target::new(target_mode, trigger, id, count)
+            .map_err(|e| Diagnostic::new(Code::InvalidTrigger, target.span, e.message()))?;
+
+        Ok(Annotation {
+            command,
+            target,
+            meta,
         })
     }
 }
 
+// Points at `name` in the `rule` parameter or in the `rule=<name>` term of `when`.
+fn unknown_rule(name: &str, sources: &[Option<Value>], declared: &Rules) -> Diagnostic {
+    let term = format!("rule={name}");
+    let span = sources
+        .iter()
+        .flatten()
+        .find_map(|value| {
+            let start = if value.text == name {
+                value.offset()
+            } else {
+                let (index, _) = value.text.match_indices(&term).find(|(index, _)| {
+                    value.text[index + term.len()..]
+                        .chars()
+                        .next()
+                        .is_none_or(|c| c == ',' || c == ')' || c.is_whitespace())
+                })?;
+                value.offset() + index + "rule=".len()
+            };
+            Some(Span::new(start, start + name.len()))
+        })
+        .unwrap_or(Span::new(0, 0));
+    Diagnostic::new(
+        Code::UnknownRule,
+        span,
+        format!("rule `{name}` is not declared in .torin.toml"),
+    )
+    .suggest(name, declared.iter().map(|(pattern, _)| pattern), |rule| {
+        format!("rule={rule}")
+    })
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::config::language::Blocks;
     use crate::model::{Date, Rule};
 
     use super::*;
@@ -46,6 +300,7 @@ mod tests {
     #[test]
     fn test_in_code_config() {
         testing::with_trace(|| {
+            let parser = Parser::new(&Syntax::default(), &Settings::default())?;
             struct Case {
                 input: &'static str,
                 expected: std::result::Result<Annotation, ()>,
@@ -54,38 +309,240 @@ mod tests {
                 Case {
                     input: "// torin DELETE BEGIN rule=foo",
                     expected: Ok(Annotation {
-                        command: model::Command::Delete,
-                        target: model::Target::Begin(model::Trigger::Rule(Rule::new("foo"))),
+                        command: Some(model::Command::Delete),
+                        target: model::Target::Begin(model::Trigger::Rule(Rule::new("foo")), None),
+                        meta: model::Meta::default(),
                     }),
                 },
                 Case {
                     input: "// torin ERROR END",
                     expected: Ok(Annotation {
-                        command: model::Command::Error,
-                        target: model::Target::End,
+                        command: Some(model::Command::Error),
+                        target: model::Target::End(None),
+                        meta: model::Meta::default(),
                     }),
                 },
                 Case {
                     input: "// torin ERROR END rule=foo",
                     expected: Err(()),
                 },
+                Case {
+                    input: "// torin END",
+                    expected: Ok(Annotation {
+                        command: None,
+                        target: model::Target::End(None),
+                        meta: model::Meta::default(),
+                    }),
+                },
+                Case {
+                    input: "// torin ELSE id=auth_v1",
+                    expected: Ok(Annotation {
+                        command: None,
+                        target: model::Target::Else(Some("auth_v1".to_string())),
+                        meta: model::Meta::default(),
+                    }),
+                },
+                Case {
+                    input: "// torin ELSE rule=foo",
+                    expected: Err(()),
+                },
+                Case {
+                    input: "// torin REQUIRE BEGIN id=audit",
+                    expected: Ok(Annotation {
+                        command: Some(model::Command::Require),
+                        target: model::Target::Begin(
+                            model::Trigger::Always,
+                            Some("audit".to_string()),
+                        ),
+                        meta: model::Meta::default(),
+                    }),
+                },
+                Case {
+                    input: "// torin REQUIRE BEGIN",
+                    expected: Err(()),
+                },
+                Case {
+                    input: "// torin REQUIRE BEGIN id=audit rule=foo",
+                    expected: Err(()),
+                },
+                Case {
+                    input: "// torin REQUIRE NEXT",
+                    expected: Err(()),
+                },
                 Case {
                     input: "// torin DELETE NEIGHBOR rule=bar",
                     expected: Ok(Annotation {
-                        command: model::Command::Delete,
+                        command: Some(model::Command::Delete),
                         target: model::Target::Neighbor(model::Trigger::Rule(Rule::new("bar"))),
+                        meta: model::Meta::default(),
                     }),
                 },
                 Case {
                     input: "// torin DELETE BEGIN date=2023-10-01",
                     expected: Ok(Annotation {
-                        command: model::Command::Delete,
-                        target: model::Target::Begin(model::Trigger::Date(Date::new(2023, 10, 1))),
+                        command: Some(model::Command::Delete),
+                        target: model::Target::Begin(
+                            model::Trigger::Date(Date::new(2023, 10, 1)),
+                            None,
+                        ),
+                        meta: model::Meta::default(),
+                    }),
+                },
+                Case {
+                    input: "// torin DELETE NEXT date=2025-12-20..2026-01-05",
+                    expected: Ok(Annotation {
+                        command: Some(model::Command::Delete),
+                        target: model::Target::Next(
+                            model::Trigger::Window(Date::new(2025, 12, 20), Date::new(2026, 1, 5)),
+                            1,
+                        ),
+                        meta: model::Meta::default(),
+                    }),
+                },
+                Case {
+                    input: "// torin DELETE ITEM version>=2.0.0",
+                    expected: Ok(Annotation {
+                        command: Some(model::Command::Delete),
+                        target: model::Target::Item(model::Trigger::Version(
+                            semver::VersionReq::parse(">=2.0.0").unwrap(),
+                        )),
+                        meta: model::Meta::default(),
+                    }),
+                },
+                Case {
+                    input: "// torin DELETE LINE dep=tokio<1.20",
+                    expected: Ok(Annotation {
+                        command: Some(model::Command::Delete),
+                        target: model::Target::Line(model::Trigger::Dep(
+                            "tokio".to_string(),
+                            semver::VersionReq::parse("<1.20").unwrap(),
+                        )),
+                        meta: model::Meta::default(),
+                    }),
+                },
+                Case {
+                    input: "// torin DELETE ITEM feature=legacy_api",
+                    expected: Ok(Annotation {
+                        command: Some(model::Command::Delete),
+                        target: model::Target::Item(model::Trigger::Feature(
+                            "legacy_api".to_string(),
+                        )),
+                        meta: model::Meta::default(),
+                    }),
+                },
+                Case {
+                    input: "// torin ERROR NEIGHBOR env=DEPLOY_TARGET:prod",
+                    expected: Ok(Annotation {
+                        command: Some(model::Command::Error),
+                        target: model::Target::Neighbor(model::Trigger::Env(
+                            "DEPLOY_TARGET".to_string(),
+                            Some("prod".to_string()),
+                        )),
+                        meta: model::Meta::default(),
+                    }),
+                },
+                Case {
+                    input: "// torin DELETE NEXT ttl=6w",
+                    expected: Ok(Annotation {
+                        command: Some(model::Command::Delete),
+                        target: model::Target::Next(
+                            model::Trigger::Ttl(model::Ttl::try_from("6w").unwrap()),
+                            1,
+                        ),
+                        meta: model::Meta::default(),
+                    }),
+                },
+                Case {
+                    input: r#"// torin DELETE BEGIN when="all(date=2026-01-01, not(rule=legacy))""#,
+                    expected: Ok(Annotation {
+                        command: Some(model::Command::Delete),
+                        target: model::Target::Begin(
+                            model::Trigger::All(vec![
+                                model::Trigger::Date(Date::new(2026, 1, 1)),
+                                model::Trigger::Not(Box::new(model::Trigger::rule("legacy"))),
+                            ]),
+                            None,
+                        ),
+                        meta: model::Meta::default(),
+                    }),
+                },
+                Case {
+                    input: r#"// torin DELETE BEGIN date=2023-10-01 reason="remove after v2 migration" owner=@alice ticket=PROJ-12"#,
+                    expected: Ok(Annotation {
+                        command: Some(model::Command::Delete),
+                        target: model::Target::Begin(
+                            model::Trigger::Date(Date::new(2023, 10, 1)),
+                            None,
+                        ),
+                        meta: model::Meta {
+                            reason: Some("remove after v2 migration".to_string()),
+                            owner: Some("@alice".to_string()),
+                            ticket: Some("PROJ-12".to_string()),
+                        },
+                    }),
+                },
+                Case {
+                    input: r#"// torin DELETE BEGIN date=2023-10-01 reason="unterminated"#,
+                    expected: Err(()),
+                },
+                Case {
+                    input: "// torin DELETE BEGIN rule=foo id=auth_v1",
+                    expected: Ok(Annotation {
+                        command: Some(model::Command::Delete),
+                        target: model::Target::Begin(
+                            model::Trigger::Rule(Rule::new("foo")),
+                            Some("auth_v1".to_string()),
+                        ),
+                        meta: model::Meta::default(),
+                    }),
+                },
+                Case {
+                    input: "// torin DELETE END id=auth_v1",
+                    expected: Ok(Annotation {
+                        command: Some(model::Command::Delete),
+                        target: model::Target::End(Some("auth_v1".to_string())),
+                        meta: model::Meta::default(),
                     }),
                 },
+                Case {
+                    input: "// torin DELETE NEIGHBOR rule=foo id=auth_v1",
+                    expected: Err(()),
+                },
+                Case {
+                    input: "// torin DELETE NEXT n=3 rule=foo",
+                    expected: Ok(Annotation {
+                        command: Some(model::Command::Delete),
+                        target: model::Target::Next(model::Trigger::Rule(Rule::new("foo")), 3),
+                        meta: model::Meta::default(),
+                    }),
+                },
+                Case {
+                    input: "// torin DELETE PREV rule=foo",
+                    expected: Ok(Annotation {
+                        command: Some(model::Command::Delete),
+                        target: model::Target::Prev(model::Trigger::Rule(Rule::new("foo")), 1),
+                        meta: model::Meta::default(),
+                    }),
+                },
+                Case {
+                    input: "// torin DELETE LINE rule=foo",
+                    expected: Ok(Annotation {
+                        command: Some(model::Command::Delete),
+                        target: model::Target::Line(model::Trigger::Rule(Rule::new("foo"))),
+                        meta: model::Meta::default(),
+                    }),
+                },
+                Case {
+                    input: "// torin DELETE NEXT n=0 rule=foo",
+                    expected: Err(()),
+                },
+                Case {
+                    input: "// torin DELETE LINE n=2 rule=foo",
+                    expected: Err(()),
+                },
             ];
             for case in cases {
-                let got = Annotation::parse(case.input);
+                let got = parser.parse(case.input);
                 match case.expected {
                     Ok(expected) => {
                         assert!(got.is_ok(), "Failed to parse: `{}`", case.input);
@@ -97,4 +554,365 @@ mod tests {
             Ok(())
         });
     }
+
+    #[test]
+    fn test_comment_syntaxes() {
+        testing::with_trace(|| {
+            struct Case {
+                syntax: Syntax,
+                input: &'static str,
+            }
+            let cases = [
+                Case {
+                    syntax: Syntax::line("#"),
+                    input: "    # torin DELETE BEGIN rule=foo",
+                },
+                Case {
+                    syntax: Syntax::line("--"),
+                    input: "-- torin DELETE BEGIN rule=foo",
+                },
+                Case {
+                    syntax: Syntax::line(";"),
+                    input: "; torin DELETE BEGIN rule=foo",
+                },
+                Case {
+                    syntax: Syntax::block("<!--", "-->"),
+                    input: "  <!-- torin DELETE BEGIN rule=foo -->",
+                },
+                Case {
+                    syntax: Syntax::block("/*", "*/"),
+                    input: "/* torin DELETE BEGIN rule=foo */",
+                },
+            ];
+            let expected = Annotation {
+                command: Some(model::Command::Delete),
+                target: model::Target::Begin(model::Trigger::Rule(Rule::new("foo")), None),
+                meta: model::Meta::default(),
+            };
+            for case in cases {
+                let parser = Parser::new(&case.syntax, &Settings::default())?;
+                assert!(parser.is_match(case.input), "Not matched: `{}`", case.input);
+                assert_eq!(parser.parse(case.input)?, expected, "{}", case.input);
+                assert!(
+                    !Parser::new(&Syntax::default(), &Settings::default())?.is_match(case.input)
+                );
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_custom_marker_and_aliases() {
+        testing::with_trace(|| {
+            let settings = Settings {
+                marker: "@sunset".to_string(),
+                aliases: [("REMOVE", "DELETE"), ("FAIL", "ERROR"), ("START", "BEGIN")]
+                    .into_iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            };
+            let parser = Parser::new(&Syntax::default(), &settings)?;
+            assert!(!parser.is_match("// torin DELETE BEGIN rule=foo"));
+            assert_eq!(
+                parser.parse("// @sunset REMOVE START rule=foo")?,
+                Annotation {
+                    command: Some(model::Command::Delete),
+                    target: model::Target::Begin(model::Trigger::Rule(Rule::new("foo")), None),
+                    meta: model::Meta::default(),
+                }
+            );
+            assert_eq!(
+                parser.parse("// @sunset FAIL END")?,
+                Annotation {
+                    command: Some(model::Command::Error),
+                    target: model::Target::End(None),
+                    meta: model::Meta::default(),
+                }
+            );
+            assert!(parser.parse("// @sunset DELETE BEGIN rule=foo").is_ok());
+
+            let parser = Parser::new(
+                &Syntax::line("#"),
+                &Settings {
+                    marker: "TORIN:".to_string(),
+                    ..Default::default()
+                },
+            )?;
+            assert!(parser.is_match("# TORIN: DELETE NEIGHBOR date=2025-01-01"));
+            assert!(!parser.is_match("# torin DELETE NEIGHBOR date=2025-01-01"));
+
+            assert!(Parser::new(
+                &Syntax::default(),
+                &Settings {
+                    marker: "two words".to_string(),
+                    ..Default::default()
+                }
+            )
+            .is_err());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        testing::with_trace(|| {
+            let parser = Parser::new(&Syntax::default(), &Settings::default())?.rules(Rules::new(
+                [
+                    ("foo".to_string(), crate::config::rule::Rule::active()),
+                    (
+                        "experimental".to_string(),
+                        crate::config::rule::Rule::active(),
+                    ),
+                ]
+                .into(),
+            )?);
+            struct Case {
+                input: &'static str,
+                code: Code,
+                token: &'static str,
+                help: Option<&'static str>,
+            }
+            let cases = [
+                Case {
+                    input: "// torin DELETE",
+                    code: Code::IncompleteAnnotation,
+                    token: "// torin DELETE",
+                    help: None,
+                },
+                Case {
+                    input: "// torin DELTE BEGIN rule=foo",
+                    code: Code::UnknownCommand,
+                    token: "DELTE",
+                    help: Some("did you mean `DELETE`?"),
+                },
+                Case {
+                    input: "// torin DELETE BEGN rule=foo",
+                    code: Code::UnknownTarget,
+                    token: "BEGN",
+                    help: Some("did you mean `BEGIN`?"),
+                },
+                Case {
+                    input: "    // torin DELETE BEGIN dat=2025-01-01",
+                    code: Code::UnknownParameter,
+                    token: "dat",
+                    help: Some("did you mean `date=`?"),
+                },
+                Case {
+                    input: "// torin DELETE BEGIN rule=foo rule=bar",
+                    code: Code::DuplicateParameter,
+                    token: "rule=bar",
+                    help: None,
+                },
+                Case {
+                    input: "// torin DELETE BEGIN rule",
+                    code: Code::MalformedParameter,
+                    token: "rule",
+                    help: Some("did you mean `rule=...`?"),
+                },
+                Case {
+                    input: "// torin DELETE BEGIN",
+                    code: Code::InvalidTrigger,
+                    token: "BEGIN",
+                    help: None,
+                },
+                Case {
+                    input: "// torin DELETE BEGIN date=2025-1x-01",
+                    code: Code::InvalidDate,
+                    token: "2025-1x-01",
+                    help: Some("dates are written as `YYYY-MM-DD`"),
+                },
+                Case {
+                    input: "// torin DELETE BEGIN date=\"2025-1x-01\"",
+                    code: Code::InvalidDate,
+                    token: "2025-1x-01",
+                    help: Some("dates are written as `YYYY-MM-DD`"),
+                },
+                Case {
+                    input: "// torin DELETE BEGIN date=2025-02-30",
+                    code: Code::InvalidDate,
+                    token: "2025-02-30",
+                    help: Some("dates are written as `YYYY-MM-DD`"),
+                },
+                Case {
+                    input: "// torin DELETE BEGIN when=\"all(rule=foo, date=2025-1x-01)\"",
+                    code: Code::InvalidTrigger,
+                    token: "2025-1x-01",
+                    help: Some("dates are written as `YYYY-MM-DD`"),
+                },
+                Case {
+                    input: "// torin DELETE BEGIN version<2.x.y",
+                    code: Code::InvalidTrigger,
+                    token: "<2.x.y",
+                    help: Some("requirements are written like `version>=2.0.0`"),
+                },
+                Case {
+                    input: "// torin DELETE BEGIN ttl=3m",
+                    code: Code::InvalidTrigger,
+                    token: "3m",
+                    help: Some("write a count followed by `h`, `d` or `w`"),
+                },
+                Case {
+                    input: "// torin DELETE BEGIN when=either(rule=foo)",
+                    code: Code::InvalidTrigger,
+                    token: "either",
+                    help: None,
+                },
+                Case {
+                    input: "// torin DELETE BEGIN date=2025-02-01..2025-01-01",
+                    code: Code::InvalidDate,
+                    token: "2025-02-01..2025-01-01",
+                    help: Some("the end date of a window must come after its start"),
+                },
+                Case {
+                    input: "// torin DELETE BEGIN rule=expermental",
+                    code: Code::UnknownRule,
+                    token: "expermental",
+                    help: Some("did you mean `rule=experimental`?"),
+                },
+                Case {
+                    input: "// torin DELETE BEGIN when=\"any(rule=foo, not(rule=fooo))\"",
+                    code: Code::UnknownRule,
+                    token: "fooo",
+                    help: Some("did you mean `rule=foo`?"),
+                },
+                Case {
+                    input: "// torin DELETE BEGIN when=rule=foo date=2025-01-01",
+                    code: Code::InvalidTrigger,
+                    token: "2025-01-01",
+                    help: Some("move the trigger into the expression"),
+                },
+            ];
+            for case in cases {
+                let Err(d) = parser.parse(case.input) else {
+                    panic!("Expected error for input: `{}`", case.input);
+                };
+                assert_eq!(d.code(), case.code, "{}", case.input);
+                let rendered = d.at(0, case.input).render("a.rs");
+                let col = case.input.find(case.token).unwrap();
+                assert!(
+                    rendered.contains(&format!("a.rs:1:{}", col + 1)),
+                    "{rendered}"
+                );
+                assert!(
+                    rendered.contains(&format!(
+                        "{}\x1b[1;31m{}\x1b[0m",
+                        " ".repeat(col),
+                        "^".repeat(case.token.len())
+                    )),
+                    "{rendered}"
+                );
+                match case.help {
+                    Some(help) => assert!(rendered.contains(help), "{rendered}"),
+                    None => assert!(!rendered.contains("help:"), "{rendered}"),
+                }
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_groups() {
+        testing::with_trace(|| {
+            let group = model::Group {
+                trigger: model::Trigger::Date(Date::new(2026, 1, 1)),
+                meta: model::Meta {
+                    reason: Some("v2 migration".to_string()),
+                    owner: Some("@platform".to_string()),
+                    ticket: None,
+                },
+            };
+            let parser = Parser::new(&Syntax::default(), &Settings::default())?
+                .groups([("v2".to_string(), group)].into());
+            let annotation = parser.parse("// torin DELETE NEXT group=v2 owner=@alice")?;
+            assert_eq!(
+                annotation,
+                Annotation {
+                    command: Some(model::Command::Delete),
+                    target: model::Target::Next(model::Trigger::Date(Date::new(2026, 1, 1)), 1),
+                    meta: model::Meta {
+                        reason: Some("v2 migration".to_string()),
+                        owner: Some("@alice".to_string()),
+                        ticket: None,
+                    },
+                }
+            );
+            struct Case {
+                input: &'static str,
+                code: Code,
+                help: Option<&'static str>,
+            }
+            for case in [
+                Case {
+                    input: "// torin DELETE NEXT group=v3",
+                    code: Code::UnknownGroup,
+                    help: Some("did you mean `group=v2`?"),
+                },
+                Case {
+                    input: "// torin DELETE NEXT group=v2 rule=foo",
+                    code: Code::InvalidTrigger,
+                    help: Some("the trigger of a group is defined in `[groups.<name>]`"),
+                },
+                Case {
+                    input: "// torin REQUIRE BEGIN id=a group=v2",
+                    code: Code::InvalidRequirement,
+                    help: None,
+                },
+            ] {
+                let Err(d) = parser.parse(case.input) else {
+                    panic!("Expected error for input: `{}`", case.input);
+                };
+                assert_eq!(d.code(), case.code, "{}", case.input);
+                let rendered = d.at(0, case.input).render("a.rs");
+                match case.help {
+                    Some(help) => assert!(rendered.contains(help), "{rendered}"),
+                    None => assert!(!rendered.contains("help:"), "{rendered}"),
+                }
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_trailing_line() {
+        testing::with_trace(|| {
+            let settings = Settings {
+                aliases: [("ONLY".to_string(), "LINE".to_string())].into(),
+                ..Default::default()
+            };
+            let parser = Parser::new(&Syntax::line("#"), &settings)?;
+            let expected = Annotation {
+                command: Some(model::Command::Delete),
+                target: model::Target::Line(model::Trigger::Rule(Rule::new("foo"))),
+                meta: model::Meta::default(),
+            };
+            assert_eq!(
+                parser.parse("print(x)  # torin DELETE LINE rule=foo")?,
+                expected
+            );
+            assert_eq!(
+                parser.parse("print(x) # torin DELETE ONLY rule=foo")?,
+                expected
+            );
+            assert!(!parser.is_match("print(x)  # torin DELETE BEGIN rule=foo"));
+            assert!(!parser.is_match("print(x)# torin DELETE LINE rule=foo"));
+            assert!(!parser.is_match("s = \"a # torin DELETE LINE rule=foo\""));
+            assert!(!parser.is_match("s = 'a # torin DELETE LINE rule=foo'"));
+            assert_eq!(
+                parser.parse("s = \"#\"  # torin DELETE LINE rule=foo")?,
+                expected
+            );
+
+            let parser = Parser::new(&Syntax::default().with_blocks(Blocks::Rust), &settings)?;
+            assert!(!parser.is_match(r#"let s = "x // torin DELETE LINE rule=foo";"#));
+            assert!(parser.is_match("let c = '\"'; // torin DELETE LINE rule=foo"));
+            assert!(parser.is_match(r##"let s = r#"a"b"#; // torin DELETE LINE rule=foo"##));
+
+            let parser = Parser::new(&Syntax::block("<!--", "-->"), &settings)?;
+            assert_eq!(
+                parser.parse("<p>beta</p> <!-- torin DELETE LINE rule=foo -->")?,
+                expected
+            );
+            Ok(())
+        });
+    }
 }
@@ -1,39 +1,187 @@
 // SPDX-License-Identifier: MPL-2.0
-use crate::prelude::*;
+use super::token::Token;
+use crate::diagnostic::{Code, Diagnostic, Span};
+use crate::model;
 
-pub struct Params {
-    pub rule: Option<String>,
-    pub date: Option<String>,
+pub const KEYS: [&str; 14] = [
+    "rule", "date", "version", "feature", "dep", "env", "ttl", "when", "group", "id", "n",
+    "reason", "owner", "ticket",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Value {
+    pub text: String,
+    pub span: Span,
+    quoted: bool,
 }
 
-impl TryFrom<Vec<&str>> for Params {
-    type Error = Error;
+impl Value {
+    // Offset of the unquoted text within the span.
+    pub fn offset(&self) -> usize {
+        self.span.start + usize::from(self.quoted)
+    }
 
-    fn try_from(parts: Vec<&str>) -> Result<Self> {
-        let mut params = Params {
-            rule: None,
-            date: None,
+    fn parse(raw: &str, span: Span, quoted: bool) -> Result<Self, Diagnostic> {
+        let Some(inner) = raw.strip_prefix('"').filter(|_| quoted) else {
+            return Ok(Value {
+                text: raw.to_string(),
+                span,
+                quoted,
+            });
         };
-        for part in parts {
-            let [k, v] = part.sized_split::<2>("=")?;
-            match k {
-                "rule" => {
-                    if params.rule.is_some() {
-                        trace!("parameter `rule` definition is duplicated")?;
-                    } else {
-                        params.rule = Some(v.to_string());
-                    }
-                }
-                "date" => {
-                    if params.date.is_some() {
-                        trace!("parameter `date` definition is duplicated")?;
-                    } else {
-                        params.date = Some(v.to_string());
-                    }
+        let mut text = String::new();
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => text.extend(chars.next()),
+                '"' if chars.as_str().is_empty() => return Ok(Value { text, span, quoted }),
+                '"' => break,
+                c => text.push(c),
+            }
+        }
+        Err(Diagnostic::new(
+            Code::MalformedParameter,
+            span,
+            format!("unterminated or misplaced quote in `{raw}`"),
+        )
+        .help("quote the whole value, e.g. `reason=\"remove after v2\"`"))
+    }
+}
+
+#[derive(Default)]
+pub struct Params {
+    pub rule: Option<Value>,
+    pub date: Option<Value>,
+    pub version: Option<Value>,
+    pub feature: Option<Value>,
+    pub dep: Option<Value>,
+    pub env: Option<Value>,
+    pub ttl: Option<Value>,
+    pub when: Option<Value>,
+    pub group: Option<Value>,
+    pub id: Option<Value>,
+    pub n: Option<Value>,
+    pub reason: Option<Value>,
+    pub owner: Option<Value>,
+    pub ticket: Option<Value>,
+}
+
+impl Params {
+    // The single-term trigger parameters that were given, in declaration order.
+    pub fn triggers(&self) -> Vec<(&'static str, &Value)> {
+        [
+            ("rule", &self.rule),
+            ("date", &self.date),
+            ("version", &self.version),
+            ("feature", &self.feature),
+            ("dep", &self.dep),
+            ("env", &self.env),
+            ("ttl", &self.ttl),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.as_ref().map(|value| (key, value)))
+        .collect()
+    }
+}
+
+impl TryFrom<Vec<Token<'_>>> for Params {
+    type Error = Diagnostic;
+
+    fn try_from(tokens: Vec<Token<'_>>) -> Result<Self, Diagnostic> {
+        let mut params = Params::default();
+        for token in tokens {
+            let split = match model::version_requirement(token.text) {
+                Some(requirement) => Some(("version", requirement)),
+                None => token.text.split_once('='),
+            };
+            let Some((k, v)) = split else {
+                return Err(Diagnostic::new(
+                    Code::MalformedParameter,
+                    token.span,
+                    format!("expected `<key>=<value>`, found `{}`", token.text),
+                )
+                .suggest(token.text, KEYS, |key| format!("{key}=...")));
+            };
+            let key_span = Span::new(token.span.start, token.span.start + k.len());
+            let value = Value::parse(
+                v,
+                Span::new(token.span.end - v.len(), token.span.end),
+                token.quoted,
+            )?;
+            let slot = match k {
+                "rule" => &mut params.rule,
+                "date" => &mut params.date,
+                "version" => &mut params.version,
+                "feature" => &mut params.feature,
+                "dep" => &mut params.dep,
+                "env" => &mut params.env,
+                "ttl" => &mut params.ttl,
+                "when" => &mut params.when,
+                "group" => &mut params.group,
+                "id" => &mut params.id,
+                "n" => &mut params.n,
+                "reason" => &mut params.reason,
+                "owner" => &mut params.owner,
+                "ticket" => &mut params.ticket,
+                _ => {
+                    return Err(Diagnostic::new(
+                        Code::UnknownParameter,
+                        key_span,
+                        format!("unknown parameter `{k}`"),
+                    )
+                    .suggest(k, KEYS, |key| format!("{key}=")));
                 }
-                _ => trace!("Unknown parameter: {}", k)?,
+            };
+            if slot.is_some() {
+                return Err(Diagnostic::new(
+                    Code::DuplicateParameter,
+                    token.span,
+                    format!("parameter `{k}` definition is duplicated"),
+                ));
             }
+            slot.replace(value);
         }
         Ok(params)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_parse() {
+        let span = Span::new(0, 0);
+        struct Case {
+            raw: &'static str,
+            expected: Option<&'static str>,
+        }
+        for case in [
+            Case {
+                raw: "foo",
+                expected: Some("foo"),
+            },
+            Case {
+                raw: r#""remove after v2 migration""#,
+                expected: Some("remove after v2 migration"),
+            },
+            Case {
+                raw: r#""say \"hi\"""#,
+                expected: Some(r#"say "hi""#),
+            },
+            Case {
+                raw: r#""unterminated"#,
+                expected: None,
+            },
+            Case {
+                raw: r#""early"quote"#,
+                expected: None,
+            },
+        ] {
+            let got = Value::parse(case.raw, span, case.raw.starts_with('"'))
+                .ok()
+                .map(|v| v.text);
+            assert_eq!(got.as_deref(), case.expected, "{}", case.raw);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct Settings {
+    #[serde(default = "default_marker")]
+    pub marker: String,
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+}
+
+fn default_marker() -> String {
+    "torin".to_string()
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            marker: default_marker(),
+            aliases: HashMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn keyword<'a>(&'a self, word: &'a str) -> &'a str {
+        self.aliases.get(word).map(String::as_str).unwrap_or(word)
+    }
+
+    pub fn spellings<'a>(&'a self, keyword: &'a str) -> impl Iterator<Item = &'a str> {
+        std::iter::once(keyword).chain(
+            self.aliases
+                .iter()
+                .filter(move |(_, v)| *v == keyword)
+                .map(|(k, _)| k.as_str()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_deserialize() {
+        let settings: Settings = toml::from_str(
+            r#"
+            marker = "@sunset"
+            [aliases]
+            REMOVE = "DELETE"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(settings.marker, "@sunset");
+        assert_eq!(settings.keyword("REMOVE"), "DELETE");
+        assert_eq!(settings.keyword("BEGIN"), "BEGIN");
+        assert_eq!(
+            settings.spellings("DELETE").collect::<Vec<_>>(),
+            vec!["DELETE", "REMOVE"]
+        );
+
+        let settings: Settings = toml::from_str("").unwrap();
+        assert_eq!(settings, Settings::default());
+    }
+}
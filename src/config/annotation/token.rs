@@ -0,0 +1,103 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::diagnostic::Span;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token<'a> {
+    pub text: &'a str,
+    pub span: Span,
+    // Whether the value, after `key=` if any, is written in quotes.
+    pub quoted: bool,
+}
+
+impl<'a> Token<'a> {
+    fn new(line: &'a str, start: usize, end: usize) -> Self {
+        let text = &line[start..end];
+        let value = text.split_once('=').map_or(text, |(_, value)| value);
+        Token {
+            text,
+            span: Span::new(start, end),
+            quoted: value.starts_with('"'),
+        }
+    }
+}
+
+pub fn tokenize(line: &str, offset: usize, end: usize) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut start = None;
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in line[..end].char_indices().skip_while(|(i, _)| *i < offset) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            _ => {}
+        }
+        match (c.is_whitespace() && !quoted, start) {
+            (true, Some(s)) => {
+                tokens.push(Token::new(line, s, i));
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        tokens.push(Token::new(line, s, end));
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let line = "  // torin DELETE  BEGIN rule=foo -->";
+        let tokens = tokenize(line, 11, line.len() - 3);
+        assert_eq!(
+            tokens,
+            vec![
+                Token {
+                    text: "DELETE",
+                    span: Span::new(11, 17),
+                    quoted: false,
+                },
+                Token {
+                    text: "BEGIN",
+                    span: Span::new(19, 24),
+                    quoted: false,
+                },
+                Token {
+                    text: "rule=foo",
+                    span: Span::new(25, 33),
+                    quoted: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_quoted() {
+        let line = r#"// torin DELETE BEGIN reason="say \"hi\" twice" owner=me"#;
+        let texts = tokenize(line, 9, line.len())
+            .into_iter()
+            .map(|t| t.text)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            texts,
+            vec![
+                "DELETE",
+                "BEGIN",
+                r#"reason="say \"hi\" twice""#,
+                "owner=me"
+            ]
+        );
+        let quoted = tokenize(line, 9, line.len())
+            .into_iter()
+            .map(|t| t.quoted)
+            .collect::<Vec<_>>();
+        assert_eq!(quoted, vec![false, false, true, false]);
+    }
+}
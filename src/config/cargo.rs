@@ -0,0 +1,236 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::cell::OnceCell;
+use std::path::Path;
+
+use crate::model;
+use crate::prelude::*;
+
+const LOCKFILE: &str = "Cargo.lock";
+
+// What `feature`, `dep` and `version` triggers read from a Cargo package.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Cargo {
+    pub version: Option<String>,
+    pub features: Vec<String>,
+    // `None` when no Cargo.lock exists next to the manifest or in a parent directory.
+    pub packages: Option<Vec<(String, model::Version)>>,
+}
+
+impl Cargo {
+    // `None` for a manifest without `[package]`, such as a virtual workspace root.
+    pub fn load(path: &str) -> Result<Option<Self>> {
+        let manifest = std::fs::read_to_string(path)?;
+        let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+        let lock = dir
+            .ancestors()
+            .find_map(|dir| std::fs::read_to_string(dir.join(LOCKFILE)).ok());
+        Self::parse(&manifest, lock.as_deref())
+    }
+
+    fn parse(manifest: &str, lock: Option<&str>) -> Result<Option<Self>> {
+        let manifest = manifest.parse::<toml::Table>()?;
+        // Members inheriting `version.workspace = true` fall back to `[workspace.package]`.
+        let version = |package: Option<&toml::Value>| {
+            package
+                .and_then(|p| p.get("version"))
+                .and_then(toml::Value::as_str)
+                .map(str::to_string)
+        };
+        let Some(package) = manifest.get("package") else {
+            return Ok(None);
+        };
+        let declared = manifest.get("features").and_then(toml::Value::as_table);
+        let mut features: Vec<String> = declared
+            .map(|features| features.keys().cloned().collect())
+            .unwrap_or_default();
+        // Optional dependencies are implicit features unless a feature enables them as `dep:name`.
+        let explicit = declared
+            .into_iter()
+            .flat_map(|features| features.values())
+            .filter_map(toml::Value::as_array)
+            .flatten()
+            .filter_map(toml::Value::as_str)
+            .filter_map(|enabled| enabled.strip_prefix("dep:"))
+            .collect::<Vec<_>>();
+        let targets = manifest
+            .get("target")
+            .and_then(toml::Value::as_table)
+            .into_iter()
+            .flat_map(|targets| targets.values())
+            .filter_map(toml::Value::as_table);
+        for table in std::iter::once(&manifest)
+            .chain(targets)
+            .flat_map(|section| ["dependencies", "build-dependencies"].map(|key| section.get(key)))
+            .flatten()
+            .filter_map(toml::Value::as_table)
+        {
+            for (name, dependency) in table {
+                let optional = dependency
+                    .get("optional")
+                    .and_then(toml::Value::as_bool)
+                    .unwrap_or(false);
+                if optional && !explicit.contains(&name.as_str()) && !features.contains(name) {
+                    features.push(name.clone());
+                }
+            }
+        }
+        let packages = match lock {
+            Some(lock) => {
+                let mut packages = vec![];
+                let lock = lock.parse::<toml::Table>()?;
+                for package in lock
+                    .get("package")
+                    .and_then(toml::Value::as_array)
+                    .into_iter()
+                    .flatten()
+                {
+                    let field = |key| package.get(key).and_then(toml::Value::as_str);
+                    if let (Some(name), Some(version)) = (field("name"), field("version")) {
+                        packages.push((name.to_string(), model::Version::try_from(version)?));
+                    }
+                }
+                Some(packages)
+            }
+            None => None,
+        };
+        Ok(Some(Cargo {
+            version: version(Some(package))
+                .or_else(|| version(manifest.get("workspace").and_then(|w| w.get("package")))),
+            features,
+            packages,
+        }))
+    }
+}
+
+// The Cargo package of the project, read the first time a `feature`, `dep` or
+// `version` trigger needs it. A missing `[package]` is only an error when the
+// manifest was configured with `project.cargo`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Package {
+    path: Option<String>,
+    configured: bool,
+    loaded: OnceCell<Option<Cargo>>,
+}
+
+impl Package {
+    pub fn new(path: Option<String>, configured: bool) -> Self {
+        Package {
+            path,
+            configured,
+            loaded: OnceCell::new(),
+        }
+    }
+
+    pub fn get(&self) -> Result<Option<&Cargo>> {
+        if let Some(cargo) = self.loaded.get() {
+            return Ok(cargo.as_ref());
+        }
+        let cargo = match &self.path {
+            Some(path) => match Cargo::load(path)? {
+                None if self.configured => {
+                    return trace!(
+                        "`{}` has no `[package]`; point `project.cargo` at a member of the workspace",
+                        path
+                    );
+                }
+                cargo => cargo,
+            },
+            None => None,
+        };
+        Ok(self.loaded.get_or_init(|| cargo).as_ref())
+    }
+}
+
+#[cfg(test)]
+impl From<Cargo> for Package {
+    fn from(cargo: Cargo) -> Self {
+        Package {
+            loaded: OnceCell::from(Some(cargo)),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        testing::with_trace(|| {
+            let manifest = r#"
+[package]
+name = "app"
+version = "1.4.0"
+
+[features]
+default = ["tls"]
+tls = []
+"#;
+            let lock = r#"
+version = 4
+
+[[package]]
+name = "tokio"
+version = "1.38.0"
+
+[[package]]
+name = "app"
+version = "1.4.0"
+"#;
+            let cargo = Cargo::parse(manifest, Some(lock))?.expect("a package");
+            assert_eq!(cargo.version.as_deref(), Some("1.4.0"));
+            assert_eq!(cargo.features, vec!["default", "tls"]);
+            assert_eq!(
+                cargo.packages,
+                Some(vec![
+                    ("tokio".to_string(), model::Version::try_from("1.38.0")?),
+                    ("app".to_string(), model::Version::try_from("1.4.0")?),
+                ])
+            );
+
+            let manifest = r#"
+[package]
+name = "app"
+version = "1.4.0"
+
+[dependencies]
+serde = { version = "1", optional = true }
+rustls = { version = "0.23", optional = true }
+log = "0.4"
+
+[target.'cfg(unix)'.dependencies]
+nix = { version = "0.29", optional = true }
+
+[features]
+tls = ["dep:rustls"]
+"#;
+            let cargo = Cargo::parse(manifest, None)?.expect("a package");
+            assert_eq!(cargo.features, vec!["tls", "serde", "nix"]);
+
+            let workspace =
+                "[workspace]\nmembers = [\"a\"]\n\n[workspace.package]\nversion = \"2.0.0\"\n";
+            assert_eq!(Cargo::parse(workspace, None)?, None);
+
+            let member = "[package]\nname = \"b\"\nversion.workspace = true\n\n[workspace.package]\nversion = \"2.0.0\"\n";
+            let cargo = Cargo::parse(member, None)?.expect("a package");
+            assert_eq!(cargo.version.as_deref(), Some("2.0.0"));
+            assert_eq!(cargo.packages, None);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_package_workspace() {
+        testing::with_trace(|| {
+            let dir = std::env::temp_dir().join(format!("torin-workspace-{}", std::process::id()));
+            std::fs::create_dir_all(&dir)?;
+            let path = dir.join("Cargo.toml").to_string_lossy().to_string();
+            std::fs::write(&path, "[workspace]\nmembers = [\"a\"]\n")?;
+            assert_eq!(Package::new(Some(path.clone()), false).get()?, None);
+            assert!(Package::new(Some(path), true).get().is_err());
+            std::fs::remove_dir_all(&dir)?;
+            Ok(())
+        });
+    }
+}
@@ -1,5 +1,5 @@
 // SPDX-License-Identifier: MPL-2.0
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 
 const AUTHOR: &str = "ysuzuki19";
 
@@ -13,14 +13,38 @@ pub struct Cli {
     pub mode: Mode,
 }
 
+#[derive(Args, Clone, Default)]
+pub struct Options {
+    /// Evaluate version triggers against this version instead of the configured one
+    #[arg(long, value_name = "VERSION")]
+    pub version: Option<String>,
+    /// Show the annotations matched by each declared rule
+    #[arg(short, long)]
+    pub verbose: bool,
+}
+
 #[derive(Subcommand)]
 pub enum Mode {
     /// Plan diff or error
-    Plan,
+    Plan {
+        #[command(flatten)]
+        options: Options,
+    },
     /// Check if any changes or errors are detected
-    Check,
+    Check {
+        /// Fail when any WARN annotation is active
+        #[arg(long)]
+        deny_warnings: bool,
+        #[command(flatten)]
+        options: Options,
+    },
     /// Apply changes to the file
-    Apply,
+    Apply {
+        #[command(flatten)]
+        options: Options,
+    },
+    /// Record fingerprints of REQUIRE blocks in .torin.lock
+    Lock,
     /// Generate completion script
     Completion {
         /// The shell to generate the completion script for
@@ -28,3 +52,14 @@ pub enum Mode {
         shell: clap_complete::Shell,
     },
 }
+
+impl Mode {
+    pub fn options(&self) -> Options {
+        match self {
+            Mode::Plan { options } | Mode::Check { options, .. } | Mode::Apply { options } => {
+                options.clone()
+            }
+            Mode::Lock | Mode::Completion { .. } => Options::default(),
+        }
+    }
+}
@@ -0,0 +1,359 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// A Rust character literal such as `'a'` or `'\u{1F600}'`, as opposed to a lifetime.
+pub static CHAR_LITERAL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^'(?:\\.[^'\s]{0,8}|[^\\'])'").expect("Invalid regex"));
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Blocks {
+    #[default]
+    Braces,
+    Indent,
+    Rust,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize)]
+pub struct Syntax {
+    pub prefix: String,
+    #[serde(default)]
+    pub suffix: Option<String>,
+    #[serde(default)]
+    pub blocks: Blocks,
+}
+
+impl Syntax {
+    pub fn line(prefix: &str) -> Self {
+        Syntax {
+            prefix: prefix.to_string(),
+            suffix: None,
+            blocks: Blocks::Braces,
+        }
+    }
+
+    pub fn block(prefix: &str, suffix: &str) -> Self {
+        Syntax {
+            prefix: prefix.to_string(),
+            suffix: Some(suffix.to_string()),
+            blocks: Blocks::Braces,
+        }
+    }
+
+    pub fn with_blocks(mut self, blocks: Blocks) -> Self {
+        self.blocks = blocks;
+        self
+    }
+}
+
+impl Syntax {
+    // Comments out `line` after its indentation. Returns `None` for blank lines.
+    pub fn comment(&self, line: &str) -> Option<String> {
+        let body = line.trim_start();
+        if body.is_empty() {
+            return None;
+        }
+        let indent = &line[..line.len() - body.len()];
+        Some(match &self.suffix {
+            Some(suffix) => format!("{indent}{} {body} {suffix}", self.prefix),
+            None => format!("{indent}{} {body}", self.prefix),
+        })
+    }
+
+    // Strips the comment delimiters from `line`, keeping its indentation. Returns
+    // `None` for lines that are not comments.
+    pub fn uncomment(&self, line: &str) -> Option<String> {
+        let body = line.trim_start();
+        let indent = &line[..line.len() - body.len()];
+        let body = body.strip_prefix(self.prefix.as_str())?;
+        let body = body.strip_prefix(' ').unwrap_or(body);
+        let body = match &self.suffix {
+            Some(suffix) => {
+                let body = body.trim_end();
+                let body = body.strip_suffix(suffix.as_str()).unwrap_or(body);
+                body.strip_suffix(' ').unwrap_or(body)
+            }
+            None => body,
+        };
+        Some(format!("{indent}{body}"))
+    }
+
+    // Where the comment on `line` starts, skipping string and character literals.
+    // Outside Rust, `'` delimits strings.
+    pub fn comment_start(&self, line: &str) -> Option<usize> {
+        let mut delimiter = None;
+        let mut chars = line.char_indices();
+        while let Some((i, c)) = chars.next() {
+            let rest = &line[i..];
+            match delimiter {
+                Some(_) if c == '\\' => {
+                    chars.next();
+                }
+                Some(d) if c == d => delimiter = None,
+                Some(_) => {}
+                None if rest.starts_with(self.prefix.as_str()) => return Some(i),
+                None => match c {
+                    '"' | '`' => delimiter = Some(c),
+                    '\'' if self.blocks != Blocks::Rust => delimiter = Some(c),
+                    // Raw strings such as `r#"..."#` may contain unescaped quotes.
+                    'r' if self.blocks == Blocks::Rust
+                        && !line[..i].ends_with(|c: char| c.is_alphanumeric() || c == '_') =>
+                    {
+                        let hashes = rest[1..].len() - rest[1..].trim_start_matches('#').len();
+                        if rest[1 + hashes..].starts_with('"') {
+                            let close = format!("\"{}", "#".repeat(hashes));
+                            let end =
+                                rest[2 + hashes..].find(&close)? + i + 2 + hashes + close.len();
+                            while chars.offset() < end {
+                                chars.next();
+                            }
+                        }
+                    }
+                    '\'' => {
+                        if let Some(literal) = CHAR_LITERAL.find(rest) {
+                            chars
+                                .by_ref()
+                                .take(literal.as_str().chars().count() - 1)
+                                .for_each(drop);
+                        }
+                    }
+                    _ => {}
+                },
+            }
+        }
+        None
+    }
+}
+
+impl Default for Syntax {
+    fn default() -> Self {
+        Syntax::line("//")
+    }
+}
+
+const BUILTINS: &[(&[&str], &str, Option<&str>)] = &[
+    (
+        &[
+            "rs", "c", "h", "cc", "cpp", "hpp", "cs", "go", "java", "js", "jsx", "mjs", "ts",
+            "tsx", "kt", "kts", "swift", "scala", "dart", "php", "proto", "zig",
+        ],
+        "//",
+        None,
+    ),
+    (
+        &[
+            "py",
+            "sh",
+            "bash",
+            "zsh",
+            "fish",
+            "rb",
+            "pl",
+            "r",
+            "yaml",
+            "yml",
+            "toml",
+            "tf",
+            "nix",
+            "cmake",
+            "ps1",
+            "ex",
+            "exs",
+            "Dockerfile",
+            "Makefile",
+        ],
+        "#",
+        None,
+    ),
+    (&["sql", "lua", "hs", "elm", "ada"], "--", None),
+    (&["lisp", "el", "clj", "scm", "ini", "asm"], ";", None),
+    (&["html", "htm", "xml", "md", "svg"], "<!--", Some("-->")),
+    (&["css", "scss", "less"], "/*", Some("*/")),
+];
+
+const BLOCKS: &[(&str, Blocks)] = &[
+    ("py", Blocks::Indent),
+    ("yaml", Blocks::Indent),
+    ("yml", Blocks::Indent),
+    ("rs", Blocks::Rust),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Languages {
+    syntaxes: HashMap<String, Syntax>,
+}
+
+impl Languages {
+    pub fn new(overrides: &HashMap<String, Syntax>) -> Self {
+        let mut syntaxes = HashMap::new();
+        for (keys, prefix, suffix) in BUILTINS {
+            let syntax = match suffix {
+                Some(suffix) => Syntax::block(prefix, suffix),
+                None => Syntax::line(prefix),
+            };
+            for key in *keys {
+                let syntax = match BLOCKS.iter().find(|(k, _)| k == key) {
+                    Some((_, blocks)) => syntax.clone().with_blocks(*blocks),
+                    None => syntax.clone(),
+                };
+                syntaxes.insert(key.to_string(), syntax);
+            }
+        }
+        for (key, syntax) in overrides {
+            syntaxes.insert(key.trim_start_matches('.').to_string(), syntax.clone());
+        }
+        Languages { syntaxes }
+    }
+
+    pub fn syntax<S>(&self, path: S) -> Syntax
+    where
+        S: AsRef<str>,
+    {
+        let path = std::path::Path::new(path.as_ref());
+        path.extension()
+            .or_else(|| path.file_name())
+            .and_then(|key| self.syntaxes.get(key.to_string_lossy().as_ref()))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_languages() {
+        let overrides = HashMap::from([
+            (
+                "py".to_string(),
+                Syntax::line(";").with_blocks(Blocks::Indent),
+            ),
+            (".vue".to_string(), Syntax::block("<!--", "-->")),
+        ]);
+        let parsed: HashMap<String, Syntax> =
+            toml::from_str(r#"slim = { prefix = "/", blocks = "indent" }"#).unwrap();
+        assert_eq!(
+            parsed["slim"],
+            Syntax::line("/").with_blocks(Blocks::Indent)
+        );
+        let languages = Languages::new(&overrides);
+        struct Case {
+            path: &'static str,
+            expected: Syntax,
+        }
+        for case in [
+            Case {
+                path: "src/main.rs",
+                expected: Syntax::line("//").with_blocks(Blocks::Rust),
+            },
+            Case {
+                path: "deploy/values.yaml",
+                expected: Syntax::line("#").with_blocks(Blocks::Indent),
+            },
+            Case {
+                path: "scripts/run.sh",
+                expected: Syntax::line("#"),
+            },
+            Case {
+                path: "db/schema.sql",
+                expected: Syntax::line("--"),
+            },
+            Case {
+                path: "docs/index.md",
+                expected: Syntax::block("<!--", "-->"),
+            },
+            Case {
+                path: "web/style.css",
+                expected: Syntax::block("/*", "*/"),
+            },
+            Case {
+                path: "docker/Dockerfile",
+                expected: Syntax::line("#"),
+            },
+            Case {
+                path: "tools/gen.py",
+                expected: Syntax::line(";").with_blocks(Blocks::Indent),
+            },
+            Case {
+                path: "web/App.vue",
+                expected: Syntax::block("<!--", "-->"),
+            },
+            Case {
+                path: "unknown.xyz",
+                expected: Syntax::default(),
+            },
+        ] {
+            assert_eq!(languages.syntax(case.path), case.expected, "{}", case.path);
+        }
+    }
+
+    #[test]
+    fn test_comment() {
+        assert_eq!(
+            Syntax::line("//")
+                .comment("    register(router);")
+                .as_deref(),
+            Some("    // register(router);")
+        );
+        assert_eq!(
+            Syntax::block("<!--", "-->")
+                .comment("  <div>old</div>")
+                .as_deref(),
+            Some("  <!-- <div>old</div> -->")
+        );
+        assert_eq!(Syntax::line("#").comment("   "), None);
+        let syntax = Syntax::line("--");
+        let line = "  SELECT 1;";
+        assert_eq!(
+            syntax.uncomment(&syntax.comment(line).unwrap()).as_deref(),
+            Some(line)
+        );
+    }
+
+    #[test]
+    fn test_uncomment() {
+        struct Case {
+            syntax: Syntax,
+            line: &'static str,
+            expected: Option<&'static str>,
+        }
+        for case in [
+            Case {
+                syntax: Syntax::line("//"),
+                line: "    // register(router);",
+                expected: Some("    register(router);"),
+            },
+            Case {
+                syntax: Syntax::line("//"),
+                line: "//register(router);",
+                expected: Some("register(router);"),
+            },
+            Case {
+                syntax: Syntax::line("#"),
+                line: "  #   indented: true",
+                expected: Some("    indented: true"),
+            },
+            Case {
+                syntax: Syntax::block("<!--", "-->"),
+                line: "  <!-- <div>new</div> -->",
+                expected: Some("  <div>new</div>"),
+            },
+            Case {
+                syntax: Syntax::line("//"),
+                line: "    register(router);",
+                expected: None,
+            },
+        ] {
+            assert_eq!(
+                case.syntax.uncomment(case.line).as_deref(),
+                case.expected,
+                "{}",
+                case.line
+            );
+        }
+    }
+}
@@ -1,19 +1,56 @@
 // SPDX-License-Identifier: MPL-2.0
+use std::collections::HashMap;
+
 use glob::glob;
 use globset::{Glob, GlobSetBuilder};
 
+use super::annotation;
+use super::cargo::Package;
+use super::language::Syntax;
+use super::rule::{Rule, Rules};
+use crate::model;
 use crate::prelude::*;
 
+const CARGO: &str = "Cargo.toml";
+
 #[derive(Debug, Clone, serde::Deserialize)]
 pub struct Manifest {
     pub project: Project,
+    #[serde(default)]
+    pub rules: HashMap<String, Rule>,
+    #[serde(default)]
+    pub groups: HashMap<String, Group>,
+    #[serde(default)]
+    pub annotation: annotation::Settings,
+    #[serde(default)]
+    pub languages: HashMap<String, Syntax>,
 }
 
 #[derive(Debug, Clone, serde::Deserialize)]
 pub struct Project {
     pub includes: Vec<String>,
     pub excludes: Vec<String>,
+    // Shorthand for rules declared with `state = "active"` and nothing else.
+    #[serde(default)]
     pub rules: Vec<String>,
+    // The release compared against `version` triggers.
+    pub version: Option<String>,
+    // The IANA time zone that dates and "today" are read in, the machine's by default.
+    pub timezone: Option<String>,
+    // The Cargo.toml read by `feature`, `dep` and `version` triggers.
+    pub cargo: Option<String>,
+}
+
+// A `[groups.<name>]` table: one trigger shared by every `group=<name>` annotation.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Group {
+    pub date: Option<String>,
+    pub rule: Option<String>,
+    pub when: Option<String>,
+    pub reason: Option<String>,
+    pub owner: Option<String>,
+    pub ticket: Option<String>,
 }
 
 impl Manifest {
@@ -24,6 +61,79 @@ impl Manifest {
         Ok(manifest)
     }
 
+    // Every declared rule, from `[rules.<name>]` and the `project.rules` shorthand.
+    pub fn rules(&self) -> Result<Rules> {
+        let mut rules = self.rules.clone();
+        for name in &self.project.rules {
+            if rules.insert(name.clone(), Rule::active()).is_some() {
+                return trace!(
+                    "Rule `{}` is declared in both `project.rules` and `[rules.{}]`",
+                    name,
+                    name
+                );
+            }
+        }
+        Rules::new(rules)
+    }
+
+    // Every group with its trigger parsed, checked against the declared `rules`.
+    pub fn groups(&self, rules: &Rules) -> Result<HashMap<String, model::Group>> {
+        let mut groups = HashMap::new();
+        for (name, group) in &self.groups {
+            let given = [
+                ("date", &group.date),
+                ("rule", &group.rule),
+                ("when", &group.when),
+            ]
+            .into_iter()
+            .filter_map(|(key, value)| value.as_deref().map(|value| (key, value)))
+            .collect::<Vec<_>>();
+            let trigger = match given.as_slice() {
+                [("when", expr)] => model::Trigger::parse(expr),
+                [(key, value)] => model::Trigger::term(key, value),
+                _ => {
+                    return trace!(
+                        "`[groups.{}]` needs exactly one of `date`, `rule` or `when`",
+                        name
+                    );
+                }
+            };
+            let trigger = match trigger {
+                Ok(trigger) => trigger,
+                Err(e) => return trace!("Invalid trigger in `[groups.{}]`: {}", name, e.message),
+            };
+            if let Some(rule) = trigger
+                .rules()
+                .into_iter()
+                .find(|rule| rules.find(rule.name()).is_none())
+            {
+                return trace!(
+                    "`[groups.{}]` references the undeclared rule `{}`",
+                    name,
+                    rule.name()
+                );
+            }
+            let meta = model::Meta {
+                reason: group.reason.clone(),
+                owner: group.owner.clone(),
+                ticket: group.ticket.clone(),
+            };
+            groups.insert(name.clone(), model::Group { trigger, meta });
+        }
+        Ok(groups)
+    }
+
+    // The Cargo package of the project: `project.cargo`, else `Cargo.toml` if present.
+    pub fn cargo(&self) -> Package {
+        match &self.project.cargo {
+            Some(path) => Package::new(Some(path.clone()), true),
+            None if std::path::Path::new(CARGO).exists() => {
+                Package::new(Some(CARGO.to_string()), false)
+            }
+            None => Package::new(None, false),
+        }
+    }
+
     pub fn sources(&self) -> Result<Vec<String>> {
         let set = {
             let mut builder = GlobSetBuilder::new();
@@ -44,3 +154,114 @@ impl Manifest {
         Ok(srcs)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules() {
+        testing::with_trace(|| {
+            let manifest: Manifest = toml::from_str(
+                r#"
+[project]
+includes = []
+excludes = []
+rules = ["debug"]
+
+[rules.legacy_gateway]
+state = "retired"
+description = "Old payment gateway"
+owner = "@payments"
+
+[rules.beta]
+state = "active"
+expires = "2026-03-31"
+"#,
+            )?;
+            let rules = manifest.rules()?;
+            let rule = |name| rules.find(name).map(|(_, rule)| rule.clone());
+            assert_eq!(rule("debug"), Some(Rule::active()));
+            assert_eq!(
+                rule("beta").and_then(|rule| rule.expires),
+                Some(model::Date::try_from("2026-03-31")?)
+            );
+            assert_eq!(
+                rule("legacy_gateway")
+                    .unwrap_or_else(Rule::active)
+                    .to_string(),
+                r#"retired owner=@payments description="Old payment gateway""#
+            );
+
+            for invalid in [
+                "[project]\nincludes = []\nexcludes = []\n[rules.a]\nstate = \"gone\"",
+                "[project]\nincludes = []\nexcludes = []\n[rules.a]\ndescription = \"no state\"",
+                "[project]\nincludes = []\nexcludes = []\n[rules.a]\nstate = \"active\"\nexpire = \"2026-01-01\"",
+                "[project]\nincludes = []\nexcludes = []\n[rules.a]\nstate = \"active\"\nexpires = \"2026-02-30\"",
+            ] {
+                assert!(toml::from_str::<Manifest>(invalid).is_err(), "{invalid}");
+            }
+            let Err(e) = toml::from_str::<Manifest>(
+                "[project]\nincludes = []\nexcludes = []\n[rules.a]\nstate = \"active\"\nexpires = \"2026-01-01T25:00\"",
+            ) else {
+                panic!("Expected an invalid expiry");
+            };
+            assert!(e.to_string().contains(model::DATE_HELP), "{e}");
+            let manifest: Manifest = toml::from_str(
+                "[project]\nincludes = []\nexcludes = []\nrules = [\"a\"]\n[rules.a]\nstate = \"retired\"",
+            )?;
+            assert!(manifest.rules().is_err());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_groups() {
+        testing::with_trace(|| {
+            let manifest: Manifest = toml::from_str(
+                r#"
+[project]
+includes = []
+excludes = []
+rules = ["legacy"]
+
+[groups.v2_migration]
+date = "2026-01-01"
+reason = "v2 migration"
+owner = "@platform"
+
+[groups.legacy_cleanup]
+when = "all(date=2026-01-01, rule=legacy)"
+"#,
+            )?;
+            let groups = manifest.groups(&manifest.rules()?)?;
+            assert_eq!(
+                groups["v2_migration"].trigger,
+                model::Trigger::Date(model::Date::try_from("2026-01-01")?)
+            );
+            assert_eq!(
+                groups["v2_migration"].meta.owner.as_deref(),
+                Some("@platform")
+            );
+            assert_eq!(
+                groups["legacy_cleanup"].trigger,
+                model::Trigger::parse("all(date=2026-01-01, rule=legacy)").expect("valid")
+            );
+
+            for invalid in [
+                "[groups.a]\nreason = \"no trigger\"",
+                "[groups.a]\ndate = \"2026-01-01\"\nrule = \"legacy\"",
+                "[groups.a]\ndate = \"2026-02-30\"",
+                "[groups.a]\nrule = \"unknown\"",
+                "[groups.a]\nwhen = \"any(rule=legacy, rule=unknown)\"",
+            ] {
+                let content = format!(
+                    "[project]\nincludes = []\nexcludes = []\nrules = [\"legacy\"]\n{invalid}"
+                );
+                let manifest: Manifest = toml::from_str(&content)?;
+                assert!(manifest.groups(&manifest.rules()?).is_err(), "{invalid}");
+            }
+            Ok(())
+        });
+    }
+}
@@ -1,4 +1,7 @@
 // SPDX-License-Identifier: MPL-2.0
 pub mod annotation;
+pub mod cargo;
 pub mod cli;
+pub mod language;
 pub mod manifest;
+pub mod rule;
@@ -0,0 +1,219 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::collections::HashMap;
+
+use globset::{GlobBuilder, GlobMatcher};
+
+use crate::model;
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum State {
+    Active,
+    Retired,
+}
+
+// A `[rules.<name>]` table. Annotations may only reference declared rules.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    pub state: State,
+    pub description: Option<String>,
+    pub owner: Option<String>,
+    // An active rule counts as retired from this date on.
+    #[serde(default, deserialize_with = "date")]
+    pub expires: Option<model::Date>,
+}
+
+impl Rule {
+    pub fn active() -> Self {
+        Rule {
+            state: State::Active,
+            description: None,
+            owner: None,
+            expires: None,
+        }
+    }
+}
+
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = match self.state {
+            State::Active => "active",
+            State::Retired => "retired",
+        };
+        write!(f, "{state}")?;
+        if let Some(expires) = &self.expires {
+            write!(f, " expires={expires}")?;
+        }
+        if let Some(owner) = &self.owner {
+            write!(f, " owner={owner}")?;
+        }
+        if let Some(description) = &self.description {
+            write!(f, " description={description:?}")?;
+        }
+        Ok(())
+    }
+}
+
+fn date<'de, D>(deserializer: D) -> std::result::Result<Option<model::Date>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let Some(text) = <Option<String> as serde::Deserialize>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    model::Date::try_from(text.as_str()).map(Some).map_err(|_| {
+        serde::de::Error::custom(format!("invalid date `{text}`: {}", model::DATE_HELP))
+    })
+}
+
+// The declared rules. A declaration may be a glob pattern such as `payments/*`,
+// where `*` stays within one `/`-separated segment and `**` crosses them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rules {
+    declared: Vec<Declaration>,
+}
+
+#[derive(Debug, Clone)]
+struct Declaration {
+    pattern: String,
+    // `None` for names that are matched literally.
+    matcher: Option<GlobMatcher>,
+    rule: Rule,
+}
+
+impl PartialEq for Declaration {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern && self.rule == other.rule
+    }
+}
+
+impl Declaration {
+    // Patterns with a longer literal prefix are more specific; on a tie `*` beats `**`.
+    fn specificity(&self) -> (usize, std::cmp::Reverse<usize>, usize) {
+        let prefix = self.pattern.find(GLOB).unwrap_or(self.pattern.len());
+        (
+            prefix,
+            std::cmp::Reverse(self.pattern.matches("**").count()),
+            self.pattern.len(),
+        )
+    }
+}
+
+const GLOB: [char; 4] = ['*', '?', '[', '{'];
+
+impl Rules {
+    pub fn new(rules: HashMap<String, Rule>) -> Result<Self> {
+        let mut declared = vec![];
+        for (pattern, rule) in rules {
+            // Names that are not valid globs, such as `a/[`, are still matched literally.
+            let matcher = pattern
+                .contains(GLOB)
+                .then(|| GlobBuilder::new(&pattern).literal_separator(true).build())
+                .and_then(|glob| glob.ok())
+                .map(|glob| glob.compile_matcher());
+            declared.push(Declaration {
+                pattern,
+                matcher,
+                rule,
+            });
+        }
+        declared.sort_by(|a, b| a.pattern.cmp(&b.pattern));
+        Ok(Rules { declared })
+    }
+
+    // The declaration `name` falls under: its exact name, else the most specific
+    // matching pattern.
+    pub fn find(&self, name: &str) -> Option<(&str, &Rule)> {
+        self.declared
+            .iter()
+            .find(|d| d.pattern == name)
+            .or_else(|| {
+                self.declared
+                    .iter()
+                    .filter(|d| d.matcher.as_ref().is_some_and(|m| m.is_match(name)))
+                    .max_by_key(|d| d.specificity())
+            })
+            .map(|d| (d.pattern.as_str(), &d.rule))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Rule)> {
+        self.declared.iter().map(|d| (d.pattern.as_str(), &d.rule))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        testing::with_trace(|| {
+            let retired = Rule {
+                state: State::Retired,
+                ..Rule::active()
+            };
+            let rules = Rules::new(HashMap::from([
+                ("debug".to_string(), Rule::active()),
+                ("payments/*".to_string(), retired.clone()),
+                ("payments/card/**".to_string(), Rule::active()),
+                ("payments/card/visa".to_string(), retired.clone()),
+                ("billing/*".to_string(), Rule::active()),
+                ("billing/**".to_string(), retired.clone()),
+                ("a/[".to_string(), Rule::active()),
+                ("what?".to_string(), Rule::active()),
+            ]))?;
+            struct Case {
+                name: &'static str,
+                expected: Option<&'static str>,
+            }
+            for case in [
+                Case {
+                    name: "debug",
+                    expected: Some("debug"),
+                },
+                Case {
+                    name: "payments/legacy_gateway",
+                    expected: Some("payments/*"),
+                },
+                Case {
+                    name: "payments/card/amex",
+                    expected: Some("payments/card/**"),
+                },
+                Case {
+                    name: "payments/card/visa",
+                    expected: Some("payments/card/visa"),
+                },
+                Case {
+                    name: "payments/bank/sepa/legacy",
+                    expected: None,
+                },
+                Case {
+                    name: "debugger",
+                    expected: None,
+                },
+                Case {
+                    name: "billing/invoice",
+                    expected: Some("billing/*"),
+                },
+                Case {
+                    name: "billing/invoice/pdf",
+                    expected: Some("billing/**"),
+                },
+                Case {
+                    name: "a/[",
+                    expected: Some("a/["),
+                },
+                Case {
+                    name: "what?",
+                    expected: Some("what?"),
+                },
+            ] {
+                let found = rules.find(case.name).map(|(pattern, _)| pattern);
+                assert_eq!(found, case.expected, "{}", case.name);
+            }
+            Ok(())
+        });
+    }
+}
@@ -1,22 +1,118 @@
 // SPDX-License-Identifier: MPL-2.0
-use super::prelude::*;
+use std::collections::HashMap;
 
 use crate::config;
+use crate::diagnostic::{Code, Diagnostic, Span};
 use crate::model;
 
+impl From<&config::annotation::Params> for model::Meta {
+    fn from(params: &config::annotation::Params) -> Self {
+        let text =
+            |value: &Option<config::annotation::Value>| value.as_ref().map(|v| v.text.clone());
+        model::Meta {
+            reason: text(&params.reason),
+            owner: text(&params.owner),
+            ticket: text(&params.ticket),
+        }
+    }
+}
+
+fn trigger_error(
+    code: Code,
+    value: &config::annotation::Value,
+    e: model::TriggerError,
+) -> Diagnostic {
+    let offset = value.offset();
+    let diagnostic = Diagnostic::new(
+        code,
+        Span::new(offset + e.span.start, offset + e.span.end),
+        e.message,
+    );
+    match e.help {
+        Some(help) => diagnostic.help(help),
+        None => diagnostic,
+    }
+}
+
+// Resolves `group=<name>` to the trigger and metadata declared in `[groups.<name>]`.
+pub fn group<'a>(
+    params: &config::annotation::Params,
+    value: &config::annotation::Value,
+    groups: &'a HashMap<String, model::Group>,
+) -> Result<&'a model::Group, Diagnostic> {
+    let triggers = params.triggers().into_iter().map(|(_, value)| value);
+    if let Some(other) = triggers.chain(params.when.as_ref()).next() {
+        return Err(Diagnostic::new(
+            Code::InvalidTrigger,
+            other.span,
+            "`group` cannot be combined with other triggers",
+        )
+        .help("the trigger of a group is defined in `[groups.<name>]` of .torin.toml"));
+    }
+    groups.get(&value.text).ok_or_else(|| {
+        Diagnostic::new(
+            Code::UnknownGroup,
+            value.span,
+            format!("group `{}` is not declared in .torin.toml", value.text),
+        )
+        .suggest(&value.text, groups.keys().map(String::as_str), |group| {
+            format!("group={group}")
+        })
+    })
+}
+
 impl TryFrom<config::annotation::Params> for Option<model::Trigger> {
-    type Error = Error;
+    type Error = Diagnostic;
 
-    fn try_from(params: config::annotation::Params) -> Result<Self> {
-        match (params.rule, params.date) {
-            (Some(rule), None) => Ok(Some(model::Trigger::Rule(model::Rule::new(rule)))),
-            (None, Some(date)) => Ok(Some(model::Trigger::Date(model::Date::try_from(date)?))),
-            (Some(rule), Some(date)) => trace!(
-                "Only one trigger type is allowed (`rule`={}, `date`={})",
-                rule,
-                date
-            )?,
-            (None, None) => Ok(None),
+    fn try_from(params: config::annotation::Params) -> Result<Self, Diagnostic> {
+        let given = params.triggers();
+        if let Some(when) = &params.when {
+            if let Some((_, other)) = given.first() {
+                return Err(Diagnostic::new(
+                    Code::InvalidTrigger,
+                    other.span,
+                    "`when` cannot be combined with other triggers",
+                )
+                .help("move the trigger into the expression, e.g. `when=all(rule=foo,date=2026-01-01)`"));
+            }
+            return model::Trigger::parse(&when.text)
+                .map(Some)
+                .map_err(|e| trigger_error(Code::InvalidTrigger, when, e));
+        }
+        match given.as_slice() {
+            [] => Ok(None),
+            [(key, value)] => {
+                let code = match *key {
+                    "date" => Code::InvalidDate,
+                    _ => Code::InvalidTrigger,
+                };
+                model::Trigger::term(key, &value.text)
+                    .map(Some)
+                    .map_err(|e| trigger_error(code, value, e))
+            }
+            [(k1, v1), (k2, v2), ..] => Err(Diagnostic::new(
+                Code::InvalidTrigger,
+                Span::new(
+                    given
+                        .iter()
+                        .map(|(_, v)| v.span.start)
+                        .min()
+                        .unwrap_or(v1.span.start),
+                    given
+                        .iter()
+                        .map(|(_, v)| v.span.end)
+                        .max()
+                        .unwrap_or(v2.span.end),
+                ),
+                format!(
+                    "only one trigger type is allowed (`{k1}`={}, `{k2}`={})",
+                    v1.text, v2.text
+                ),
+            )
+            .help(format!(
+                "combine triggers with `when`, e.g. `when=all({k1}={},{k2}={})`",
+                v1.text, v2.text
+            ))),
         }
     }
 }
@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: MPL-2.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Code {
+    IncompleteAnnotation,
+    UnknownCommand,
+    UnknownParameter,
+    UnknownTarget,
+    DuplicateParameter,
+    MalformedParameter,
+    InvalidTrigger,
+    InvalidDate,
+    UnclosedBlock,
+    UnexpectedAnnotation,
+    DuplicateId,
+    MismatchedId,
+    UnexpectedParameter,
+    UnresolvedItem,
+    InvalidRequirement,
+    UnknownRule,
+    UnknownGroup,
+}
+
+impl Code {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Code::IncompleteAnnotation => "T0001",
+            Code::UnknownCommand => "T0002",
+            Code::UnknownParameter => "T0003",
+            Code::UnknownTarget => "T0004",
+            Code::DuplicateParameter => "T0005",
+            Code::MalformedParameter => "T0006",
+            Code::InvalidTrigger => "T0007",
+            Code::InvalidDate => "T0008",
+            Code::UnclosedBlock => "T0009",
+            Code::UnexpectedAnnotation => "T0010",
+            Code::DuplicateId => "T0011",
+            Code::MismatchedId => "T0012",
+            Code::UnexpectedParameter => "T0013",
+            Code::UnresolvedItem => "T0014",
+            Code::InvalidRequirement => "T0015",
+            Code::UnknownRule => "T0016",
+            Code::UnknownGroup => "T0017",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Code::IncompleteAnnotation => "incomplete annotation",
+            Code::UnknownCommand => "unknown command",
+            Code::UnknownParameter => "unknown parameter",
+            Code::UnknownTarget => "unknown target",
+            Code::DuplicateParameter => "duplicate parameter",
+            Code::MalformedParameter => "malformed parameter",
+            Code::InvalidTrigger => "invalid trigger",
+            Code::InvalidDate => "invalid date",
+            Code::UnclosedBlock => "unclosed block",
+            Code::UnexpectedAnnotation => "unexpected annotation",
+            Code::DuplicateId => "duplicate id",
+            Code::MismatchedId => "mismatched id",
+            Code::UnexpectedParameter => "unexpected parameter",
+            Code::UnresolvedItem => "unresolved item",
+            Code::InvalidRequirement => "invalid requirement",
+            Code::UnknownRule => "unknown rule",
+            Code::UnknownGroup => "unknown group",
+        }
+    }
+}
+
+impl std::fmt::Display for Code {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.id(), self.title())
+    }
+}
@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: MPL-2.0
+mod code;
+pub mod suggest;
+
+use crate::model::cutify::CutifyOps;
+pub use code::Code;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Source {
+    index: usize,
+    line: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    code: Code,
+    message: String,
+    span: Span,
+    help: Option<String>,
+    source: Option<Source>,
+}
+
+impl Diagnostic {
+    pub fn new<S>(code: Code, span: Span, message: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        Diagnostic {
+            code,
+            message: message.as_ref().to_string(),
+            span,
+            help: None,
+            source: None,
+        }
+    }
+
+    pub fn help<S>(mut self, help: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.help = Some(help.as_ref().to_string());
+        self
+    }
+
+    pub fn suggest<'a, I>(self, word: &str, candidates: I, format: impl Fn(&str) -> String) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        match suggest::closest(word, candidates) {
+            Some(candidate) => self.help(format!("did you mean `{}`?", format(candidate))),
+            None => self,
+        }
+    }
+
+    pub fn at(mut self, index: usize, line: &str) -> Self {
+        self.source = Some(Source {
+            index,
+            line: line.to_string(),
+        });
+        self
+    }
+
+    pub fn code(&self) -> Code {
+        self.code
+    }
+
+    pub fn render(&self, path: &str) -> String {
+        let header = format!("error[{}]: {}", self.code.id(), self.message);
+        let mut out = vec![header.cutify().red().bold().to_string()];
+        match &self.source {
+            Some(source) => {
+                let lineno = (source.index + 1).to_string();
+                let pad = " ".repeat(lineno.len());
+                let col = source.line[..self.span.start.min(source.line.len())]
+                    .chars()
+                    .count();
+                let width = source
+                    .line
+                    .get(self.span.start..self.span.end)
+                    .map(|s| s.chars().count())
+                    .unwrap_or_default()
+                    .max(1);
+                out.push(format!("{pad}--> {}:{}:{}", path, lineno, col + 1));
+                out.push(format!("{pad} |"));
+                out.push(format!("{lineno} | {}", source.line));
+                let caret = "^".repeat(width);
+                out.push(format!(
+                    "{pad} | {}{}",
+                    " ".repeat(col),
+                    caret.cutify().red().bold()
+                ));
+            }
+            None => out.push(format!("--> {path}")),
+        }
+        if let Some(help) = &self.help {
+            out.push(format!(
+                "{} = help: {}",
+                " ".repeat(self.lineno_width()),
+                help
+            ));
+        }
+        out.join("\n")
+    }
+
+    fn lineno_width(&self) -> usize {
+        self.source
+            .as_ref()
+            .map(|source| (source.index + 1).to_string().len())
+            .unwrap_or_default()
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error[{}]: {}", self.code.id(), self.message)?;
+        if let Some(source) = &self.source {
+            write!(f, " (line {})", source.index + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostics(pub Vec<Diagnostic>);
+
+impl Diagnostics {
+    pub fn render(&self, path: &str) -> String {
+        self.0
+            .iter()
+            .map(|d| d.render(path))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+impl std::fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for d in &self.0 {
+            writeln!(f, "{d}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostics {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let line = "    // torin DELETE BEGIN dat=2025-01-01";
+        let start = line.find("dat").unwrap();
+        let d = Diagnostic::new(
+            Code::UnknownParameter,
+            Span::new(start, start + 3),
+            "unknown parameter `dat`",
+        )
+        .suggest("dat", ["rule", "date"], |c| format!("{c}="))
+        .at(11, line);
+        assert_eq!(d.code(), Code::UnknownParameter);
+        assert_eq!(
+            d.render("src/lib.rs"),
+            [
+                "\x1b[1;31merror[T0003]: unknown parameter `dat`\x1b[0m",
+                "  --> src/lib.rs:12:27",
+                "   |",
+                "12 |     // torin DELETE BEGIN dat=2025-01-01",
+                "   |                           \x1b[1;31m^^^\x1b[0m",
+                "   = help: did you mean `date=`?",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            d.to_string(),
+            "error[T0003]: unknown parameter `dat` (line 12)"
+        );
+    }
+}
@@ -1,10 +1,13 @@
 // SPDX-License-Identifier: MPL-2.0
 use super::context;
 use super::file;
+use super::lock;
 use super::mode;
 use super::plan;
 use super::plan::Prune;
-use super::Status;
+use super::summary::Summary;
+use crate::config;
+use crate::diagnostic::Diagnostics;
 use crate::model;
 use crate::model::cutify::CutifyOps;
 use crate::prelude::*;
@@ -18,54 +21,717 @@ impl Action {
         Action { mode }
     }
 
-    pub fn run(&self, ctx: &context::Context, path: &String) -> Result<Status> {
+    pub fn run(
+        &self,
+        ctx: &context::Context,
+        parser: &config::annotation::Parser,
+        path: &String,
+        lock: &lock::Lock,
+    ) -> Result<Summary> {
         let mut f = file::File::load(path)?;
-        let mut errors = Option::<Vec<_>>::None;
-        while let Some(plans) = plan::Plans::parse(&f.lines())?.prune(ctx)? {
-            if plans.all(|p| p.command().is_error()) {
-                errors.replace(plans.iter().cloned().collect());
-                break;
+        let Evaluation {
+            applied,
+            errors,
+            warnings,
+            requirements,
+            references,
+        } = match evaluate(ctx, parser, &mut f)? {
+            Ok(evaluation) => evaluation,
+            Err(diagnostics) => {
+                eprintln!("{}\n", diagnostics.render(path));
+                return Ok(Summary {
+                    invalid: 1,
+                    ..Default::default()
+                });
             }
-
-            let p = plans.first()?;
-            match p.command() {
-                model::Command::Delete => {
-                    f.flagging(p.begin(), p.end());
-                }
-                model::Command::Error => {}
-            }
-        }
+        };
+        let violations = lock.verify(path, &requirements);
         match self.mode {
-            mode::Mode::Plan => {
+            mode::Mode::Plan | mode::Mode::Check => {
                 for diff in f.diffs() {
                     println!("{}:{}", path.cutify().bold(), diff.lineno());
+                    print_notes(
+                        ctx,
+                        applied
+                            .iter()
+                            .filter(|(origin, _)| diff.contains(*origin))
+                            .map(|(_, p)| p),
+                    );
                     println!("{}\n", diff.unified_diff_format());
                 }
-            }
-            mode::Mode::Check => {
-                let mut succeed = true;
-                for diff in f.diffs() {
-                    println!("{}:{}", path.cutify().bold(), diff.lineno());
-                    println!("{}\n", diff.unified_diff_format());
-                    succeed = false;
-                }
 
-                if let Some(errors) = errors.take() {
-                    for p in errors {
-                        println!("check: {}:{}", path.cutify().bold(), p.begin() + 1);
-                    }
-                    succeed = false;
+                for (origin, p) in &errors {
+                    println!(
+                        "{} {}:{}",
+                        "error:".cutify().red().bold(),
+                        path.cutify().bold(),
+                        origin + 1
+                    );
+                    print_notes(ctx, [p].into_iter());
                 }
 
-                if !succeed {
-                    return Ok(Status::Failure);
+                for (origin, p) in &warnings {
+                    println!(
+                        "{} {}:{}",
+                        "warn:".cutify().yellow().bold(),
+                        path.cutify().bold(),
+                        origin + 1
+                    );
+                    print_notes(ctx, [p].into_iter());
                 }
+
+                print_violations(path, &violations);
             }
             mode::Mode::Apply => {
+                if violations.iter().any(lock::Violation::is_breach) {
+                    print_violations(path, &violations);
+                    return Ok(Summary {
+                        blocked: 1,
+                        ..Default::default()
+                    });
+                }
                 f.apply();
                 f.dump(file::Destination::Overwrite)?;
             }
+            mode::Mode::Lock => unreachable!("REQUIRE blocks are locked by the engine"),
+        };
+        Ok(Summary {
+            changes: applied.len(),
+            errors: errors.len() + violations.len(),
+            warnings: warnings.len(),
+            references: references
+                .into_iter()
+                .filter_map(|(index, rule)| {
+                    let (pattern, _) = ctx.rules().find(&rule)?;
+                    Some((
+                        pattern.to_string(),
+                        format!("{path}:{} rule={rule}", index + 1),
+                    ))
+                })
+                .collect(),
+            ..Default::default()
+        })
+    }
+}
+
+struct Evaluation {
+    applied: Vec<(usize, plan::Plan)>,
+    errors: Vec<(usize, plan::Plan)>,
+    warnings: Vec<(usize, plan::Plan)>,
+    requirements: Vec<lock::Requirement>,
+    // Every rule referenced in the file as it was loaded, by line.
+    references: Vec<(usize, String)>,
+}
+
+// Deletions are applied outermost-first and the file is re-parsed after each one,
+// so blocks nested in a deleted range disappear with it. ERROR plans that survive
+// every deletion are reported, as are WARN plans. REQUIRE blocks are fingerprinted
+// as they would look after the cleanup.
+fn evaluate(
+    ctx: &context::Context,
+    parser: &config::annotation::Parser,
+    f: &mut file::File,
+) -> Result<std::result::Result<Evaluation, Diagnostics>> {
+    let mut applied = vec![];
+    let mut references = None;
+    let mut evaluation = loop {
+        let mut parsed = match plan::Plans::parse(parser, &f.lines()) {
+            Ok(parsed) => parsed,
+            Err(diagnostics) => return Ok(Err(diagnostics)),
+        };
+        references.get_or_insert_with(|| {
+            parsed
+                .iter()
+                .flat_map(plan::Plans::iter)
+                .flat_map(|p| {
+                    p.trigger()
+                        .rules()
+                        .into_iter()
+                        .map(|rule| (p.begin(), rule.name().to_string()))
+                })
+                .collect::<Vec<_>>()
+        });
+        if let Some(plans) = &mut parsed {
+            plans.stamp(|index| {
+                let origin = f.origin(index);
+                f.committed(origin)
+            })?;
+        }
+        let Some(plans) = parsed.clone().prune(ctx)? else {
+            break Evaluation {
+                applied,
+                errors: vec![],
+                warnings: vec![],
+                requirements: vec![],
+                references: vec![],
+            };
+        };
+        let Some(p) = plans.iter().find(|p| !p.command().is_report()) else {
+            let lines = f.lines();
+            let requirements = plans
+                .iter()
+                .filter(|p| matches!(p.command(), model::Command::Require))
+                .map(|p| lock::Requirement::new(p, &lines, f.origin(p.begin())))
+                .collect();
+            let (warnings, errors) = plans
+                .iter()
+                .filter(|p| !matches!(p.command(), model::Command::Require))
+                .map(|p| (f.origin(p.begin()), p.clone()))
+                .partition(|(_, p)| matches!(p.command(), model::Command::Warn));
+            break Evaluation {
+                applied,
+                errors,
+                warnings,
+                requirements,
+                references: vec![],
+            };
+        };
+        match p.command() {
+            model::Command::Delete if p.is_file() => {
+                applied.push((f.origin(p.begin()), p.clone()));
+                f.remove();
+                break Evaluation {
+                    applied,
+                    errors: vec![],
+                    warnings: vec![],
+                    requirements: vec![],
+                    references: vec![],
+                };
+            }
+            model::Command::Delete => {
+                applied.push((f.origin(p.begin()), p.clone()));
+                // A switch-over block deletes up to its ELSE; its END becomes an orphan.
+                let end = p.otherwise().unwrap_or(p.end());
+                let lines = f.lines();
+                for (index, line) in lines.iter().enumerate().take(p.end()).skip(end + 1) {
+                    f.replace(index, line.clone());
+                }
+                let orphans = parsed
+                    .as_ref()
+                    .map(|all| all.orphans(p.begin(), end))
+                    .unwrap_or_default();
+                for orphan in orphans.iter().rev().filter(|orphan| **orphan > end) {
+                    f.flagging(*orphan, *orphan);
+                }
+                f.flagging(p.begin(), end);
+                for orphan in orphans.iter().rev().filter(|orphan| **orphan < p.begin()) {
+                    f.flagging(*orphan, *orphan);
+                }
+            }
+            model::Command::Uncomment => {
+                applied.push((f.origin(p.begin()), p.clone()));
+                rewrite(parser, f, p, config::language::Syntax::uncomment);
+            }
+            model::Command::Comment => {
+                applied.push((f.origin(p.begin()), p.clone()));
+                rewrite(parser, f, p, config::language::Syntax::comment);
+            }
+            model::Command::Unwrap => {
+                applied.push((f.origin(p.begin()), p.clone()));
+                rewrite(parser, f, p, |_, _| None);
+            }
+            model::Command::Error | model::Command::Warn | model::Command::Require => {}
+        }
+    };
+    evaluation.references = references.unwrap_or_default();
+    Ok(Ok(evaluation))
+}
+
+// Rewrites every line in the range of `p` except full-line annotations, and removes
+// its markers. Code with a trailing `LINE` annotation is rewritten as a whole.
+fn rewrite(
+    parser: &config::annotation::Parser,
+    f: &mut file::File,
+    p: &plan::Plan,
+    transform: impl Fn(&config::language::Syntax, &str) -> Option<String>,
+) {
+    let lines = f.lines();
+    for (index, line) in lines.iter().enumerate().take(p.end() + 1).skip(p.begin()) {
+        if p.markers().contains(&index) || parser.is_standalone(line) {
+            continue;
+        }
+        if let Some(line) = transform(parser.syntax(), line) {
+            f.replace(index, line);
+        }
+    }
+    for marker in p.markers().iter().rev() {
+        f.flagging(*marker, *marker);
+    }
+}
+
+pub fn print_violations(path: &str, violations: &[lock::Violation]) {
+    for violation in violations {
+        let location = match violation.origin() {
+            Some(origin) => format!("{path}:{}", origin + 1),
+            None => path.to_string(),
         };
-        Ok(Status::Success)
+        println!(
+            "{} {}: {violation}",
+            "require:".cutify().red().bold(),
+            location.cutify().bold()
+        );
+    }
+}
+
+fn print_notes<'a>(ctx: &context::Context, plans: impl Iterator<Item = &'a plan::Plan>) {
+    for p in plans {
+        if !p.meta().is_empty() {
+            println!("{} {}", "note:".cutify().bold(), p.meta());
+        }
+        for ttl in p.trigger().ttls() {
+            println!("{} {ttl}", "note:".cutify().bold());
+        }
+        for rule in p.trigger().rules() {
+            match ctx.rules().find(rule.name()) {
+                Some((pattern, definition)) if pattern != rule.name() => println!(
+                    "{} rule={} ({pattern}) {definition}",
+                    "note:".cutify().bold(),
+                    rule.name()
+                ),
+                Some((_, definition)) => {
+                    println!(
+                        "{} rule={} {definition}",
+                        "note:".cutify().bold(),
+                        rule.name()
+                    )
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use config::rule::State;
+
+    #[test]
+    fn test_evaluate_warnings() {
+        testing::with_trace(|| {
+            let parser = config::annotation::Parser::new(&Default::default(), &Default::default())?;
+            let ctx = context::Context::mock(
+                model::Date::mock(2025, 5, 20),
+                vec![
+                    ("keep", State::Active),
+                    ("debug", State::Retired),
+                    ("experiment", State::Retired),
+                ],
+            );
+            let mut f = file::File::mock(
+                [
+                    "// torin WARN NEXT date=2025-05-01 reason=\"removed in June\"",
+                    "a",
+                    "// torin ERROR NEXT rule=debug",
+                    "b",
+                    "// torin WARN NEXT rule=keep",
+                    "c",
+                ]
+                .iter()
+                .map(|l| l.to_string())
+                .collect(),
+            );
+            let Ok(evaluation) = evaluate(&ctx, &parser, &mut f)? else {
+                panic!("Unexpected diagnostics");
+            };
+            assert!(evaluation.applied.is_empty());
+            let origins =
+                |plans: &[(usize, plan::Plan)]| plans.iter().map(|(o, _)| *o).collect::<Vec<_>>();
+            assert_eq!(origins(&evaluation.warnings), vec![0]);
+            assert_eq!(origins(&evaluation.errors), vec![2]);
+            assert_eq!(
+                evaluation.references,
+                vec![(2, "debug".to_string()), (4, "keep".to_string())]
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_evaluate_ttl() {
+        testing::with_trace(|| {
+            let parser = config::annotation::Parser::new(&Default::default(), &Default::default())?;
+            let ctx = context::Context::mock(model::Date::mock(2025, 5, 20), vec![]);
+            let mut f = file::File::mock(
+                [
+                    "// torin DELETE NEXT ttl=90d",
+                    "a",
+                    "// torin DELETE NEXT ttl=90d",
+                    "b",
+                    "// torin DELETE NEXT ttl=2w",
+                    "c",
+                ]
+                .iter()
+                .map(|l| l.to_string())
+                .collect(),
+            )
+            .mock_blame(
+                vec![Some("2025-01-01T00:00:00Z".parse()?), None, None],
+                "2025-05-01T00:00:00Z".parse()?,
+            );
+            let Ok(evaluation) = evaluate(&ctx, &parser, &mut f)? else {
+                panic!("Unexpected diagnostics");
+            };
+            f.apply();
+            // Uncommitted lines count from the modification time of the file.
+            assert_eq!(f.lines(), vec!["// torin DELETE NEXT ttl=90d", "b"]);
+            let [(origin, p), (later, _)] = evaluation.applied.as_slice() else {
+                panic!("Expected two applied plans");
+            };
+            assert_eq!((*origin, *later), (0, 4));
+            assert_eq!(
+                p.trigger().ttls()[0].to_string(),
+                "ttl=90d expires 2025-04-01T00:00Z"
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_evaluate_requirements() {
+        testing::with_trace(|| {
+            let parser = config::annotation::Parser::new(&Default::default(), &Default::default())?;
+            let ctx = context::Context::mock(model::Date::mock(2025, 5, 20), vec![]);
+            let mut f = file::File::mock(
+                [
+                    "a",
+                    "// torin REQUIRE BEGIN id=audit",
+                    "// torin DELETE NEXT date=2025-01-01",
+                    "b",
+                    "c",
+                    "// torin REQUIRE END id=audit",
+                ]
+                .iter()
+                .map(|l| l.to_string())
+                .collect(),
+            );
+            let Ok(evaluation) = evaluate(&ctx, &parser, &mut f)? else {
+                panic!("Unexpected diagnostics");
+            };
+            assert!(evaluation.errors.is_empty());
+            let [requirement] = evaluation.requirements.as_slice() else {
+                panic!("Expected a single requirement");
+            };
+            assert_eq!(requirement.id, "audit");
+            assert_eq!(requirement.origin, 1);
+            // The fingerprint covers the block as it looks after the cleanup.
+            let mut cleaned = file::File::mock(
+                [
+                    "// torin REQUIRE BEGIN id=audit",
+                    "c",
+                    "// torin REQUIRE END id=audit",
+                ]
+                .iter()
+                .map(|l| l.to_string())
+                .collect(),
+            );
+            let Ok(expected) = evaluate(&ctx, &parser, &mut cleaned)? else {
+                panic!("Unexpected diagnostics");
+            };
+            assert_eq!(requirement.hash, expected.requirements[0].hash);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_evaluate_nested() {
+        testing::with_trace(|| {
+            let parser = config::annotation::Parser::new(&Default::default(), &Default::default())?;
+            let ctx = context::Context::mock(
+                model::Date::mock(2025, 5, 20),
+                vec![
+                    ("keep", State::Active),
+                    ("debug", State::Retired),
+                    ("experiment", State::Retired),
+                ],
+            );
+            struct Case {
+                name: &'static str,
+                lines: Vec<&'static str>,
+                expected: Vec<&'static str>,
+                errors: Vec<usize>,
+            }
+            let cases = [
+                Case {
+                    name: "outer active, inner inactive",
+                    lines: vec![
+                        "a",
+                        "// torin DELETE BEGIN date=2025-01-01",
+                        "b",
+                        "// torin DELETE BEGIN rule=keep",
+                        "c",
+                        "// torin DELETE END",
+                        "d",
+                        "// torin DELETE END",
+                        "e",
+                    ],
+                    expected: vec!["a", "e"],
+                    errors: vec![],
+                },
+                Case {
+                    name: "outer inactive, inner active",
+                    lines: vec![
+                        "a",
+                        "// torin DELETE BEGIN rule=keep",
+                        "b",
+                        "// torin DELETE BEGIN date=2025-01-01",
+                        "c",
+                        "// torin DELETE END",
+                        "d",
+                        "// torin DELETE END",
+                        "e",
+                    ],
+                    expected: vec![
+                        "a",
+                        "// torin DELETE BEGIN rule=keep",
+                        "b",
+                        "d",
+                        "// torin DELETE END",
+                        "e",
+                    ],
+                    errors: vec![],
+                },
+                Case {
+                    name: "error inside active delete is deleted",
+                    lines: vec![
+                        "// torin DELETE BEGIN date=2025-01-01",
+                        "// torin ERROR BEGIN rule=debug",
+                        "b",
+                        "// torin ERROR END",
+                        "// torin DELETE END",
+                        "e",
+                    ],
+                    expected: vec!["e"],
+                    errors: vec![],
+                },
+                Case {
+                    name: "error inside inactive delete is reported",
+                    lines: vec![
+                        "// torin DELETE BEGIN rule=keep",
+                        "// torin ERROR BEGIN rule=debug",
+                        "b",
+                        "// torin ERROR END",
+                        "// torin DELETE END",
+                    ],
+                    expected: vec![
+                        "// torin DELETE BEGIN rule=keep",
+                        "// torin ERROR BEGIN rule=debug",
+                        "b",
+                        "// torin ERROR END",
+                        "// torin DELETE END",
+                    ],
+                    errors: vec![1],
+                },
+                Case {
+                    name: "delete inside active error is applied",
+                    lines: vec![
+                        "a",
+                        "// torin ERROR BEGIN rule=debug",
+                        "// torin DELETE BEGIN date=2025-01-01",
+                        "c",
+                        "// torin DELETE END",
+                        "// torin ERROR END",
+                    ],
+                    expected: vec!["a", "// torin ERROR BEGIN rule=debug", "// torin ERROR END"],
+                    errors: vec![1],
+                },
+                Case {
+                    name: "interleaved labeled blocks, first active",
+                    lines: vec![
+                        "// torin DELETE BEGIN date=2025-01-01 id=a",
+                        "a",
+                        "// torin DELETE BEGIN rule=keep id=b",
+                        "ab",
+                        "// torin DELETE END id=a",
+                        "b",
+                        "// torin DELETE END id=b",
+                        "c",
+                    ],
+                    expected: vec!["b", "c"],
+                    errors: vec![],
+                },
+                Case {
+                    name: "interleaved labeled blocks, second active",
+                    lines: vec![
+                        "// torin DELETE BEGIN rule=keep id=a",
+                        "a",
+                        "// torin DELETE BEGIN date=2025-01-01 id=b",
+                        "ab",
+                        "// torin DELETE END id=a",
+                        "b",
+                        "// torin DELETE END id=b",
+                        "c",
+                    ],
+                    expected: vec!["a", "c"],
+                    errors: vec![],
+                },
+                Case {
+                    name: "file removed",
+                    lines: vec![
+                        "// torin DELETE FILE date=2025-01-01",
+                        "a",
+                        "// torin ERROR NEIGHBOR rule=debug",
+                        "b",
+                    ],
+                    expected: vec![],
+                    errors: vec![],
+                },
+                Case {
+                    name: "file kept",
+                    lines: vec!["// torin DELETE FILE rule=keep", "a"],
+                    expected: vec!["// torin DELETE FILE rule=keep", "a"],
+                    errors: vec![],
+                },
+                Case {
+                    name: "uncomment block",
+                    lines: vec![
+                        "fn main() {",
+                        "    // torin UNCOMMENT BEGIN date=2025-01-01",
+                        "    // register(router);",
+                        "",
+                        "    // torin DELETE NEXT rule=keep",
+                        "    //serve();",
+                        "    // torin UNCOMMENT END",
+                        "}",
+                    ],
+                    expected: vec![
+                        "fn main() {",
+                        "    register(router);",
+                        "",
+                        "    // torin DELETE NEXT rule=keep",
+                        "    serve();",
+                        "}",
+                    ],
+                    errors: vec![],
+                },
+                // `concat!` keeps this source line from reading as an annotation itself.
+                Case {
+                    name: "comment block with a trailing annotation",
+                    lines: vec![
+                        "// torin COMMENT BEGIN date=2025-01-01",
+                        "x = compute();",
+                        concat!("y = legacy(); //", " torin DELETE LINE date=2099-01-01"),
+                        "// torin COMMENT END",
+                    ],
+                    expected: vec![
+                        "// x = compute();",
+                        concat!("// y = legacy(); //", " torin DELETE LINE date=2099-01-01"),
+                    ],
+                    errors: vec![],
+                },
+                Case {
+                    name: "uncomment block with a trailing annotation",
+                    lines: vec![
+                        "// torin UNCOMMENT BEGIN date=2025-01-01",
+                        concat!("// y = legacy(); //", " torin DELETE LINE date=2099-01-01"),
+                        "// torin UNCOMMENT END",
+                    ],
+                    expected: vec![concat!(
+                        "y = legacy(); //",
+                        " torin DELETE LINE date=2099-01-01"
+                    )],
+                    errors: vec![],
+                },
+                Case {
+                    name: "comment item",
+                    lines: vec![
+                        "// torin COMMENT ITEM date=2025-01-01",
+                        "fn legacy() {",
+                        "",
+                        "    run();",
+                        "}",
+                        "fn kept() {}",
+                    ],
+                    expected: vec![
+                        "// fn legacy() {",
+                        "",
+                        "    // run();",
+                        "// }",
+                        "fn kept() {}",
+                    ],
+                    errors: vec![],
+                },
+                Case {
+                    name: "unwrap keeps nested blocks",
+                    lines: vec![
+                        "a",
+                        "// torin UNWRAP BEGIN rule=experiment",
+                        "b",
+                        "// torin DELETE BEGIN date=2025-01-01",
+                        "c",
+                        "// torin DELETE END",
+                        "// torin ERROR NEXT rule=debug",
+                        "d",
+                        "// torin UNWRAP END",
+                    ],
+                    expected: vec!["a", "b", "// torin ERROR NEXT rule=debug", "d"],
+                    errors: vec![6],
+                },
+                Case {
+                    name: "switch-over keeps the else branch",
+                    lines: vec![
+                        "a",
+                        "// torin DELETE BEGIN date=2025-01-01",
+                        "old();",
+                        "// torin ELSE",
+                        "new();",
+                        "// torin DELETE BEGIN date=2025-01-01 id=x",
+                        "legacy();",
+                        "// torin ELSE id=x",
+                        "modern();",
+                        "// torin END id=x",
+                        "// torin END",
+                        "b",
+                    ],
+                    expected: vec!["a", "new();", "modern();", "b"],
+                    errors: vec![],
+                },
+                Case {
+                    name: "switch-over pending",
+                    lines: vec![
+                        "// torin DELETE BEGIN rule=keep",
+                        "old();",
+                        "// torin ELSE",
+                        "new();",
+                        "// torin END",
+                    ],
+                    expected: vec![
+                        "// torin DELETE BEGIN rule=keep",
+                        "old();",
+                        "// torin ELSE",
+                        "new();",
+                        "// torin END",
+                    ],
+                    errors: vec![],
+                },
+                Case {
+                    name: "uncomment pending",
+                    lines: vec!["// torin UNCOMMENT NEXT rule=keep", "// serve();"],
+                    expected: vec!["// torin UNCOMMENT NEXT rule=keep", "// serve();"],
+                    errors: vec![],
+                },
+                Case {
+                    name: "nothing triggered",
+                    lines: vec!["// torin ERROR BEGIN rule=keep", "b", "// torin ERROR END"],
+                    expected: vec!["// torin ERROR BEGIN rule=keep", "b", "// torin ERROR END"],
+                    errors: vec![],
+                },
+            ];
+            for case in cases {
+                let mut f = file::File::mock(case.lines.iter().map(|l| l.to_string()).collect());
+                let Ok(evaluation) = evaluate(&ctx, &parser, &mut f)? else {
+                    panic!("Unexpected diagnostics: {}", case.name);
+                };
+                f.apply();
+                assert_eq!(f.lines(), case.expected, "{}", case.name);
+                let errors = evaluation
+                    .errors
+                    .iter()
+                    .map(|(origin, _)| *origin)
+                    .collect::<Vec<_>>();
+                assert_eq!(errors, case.errors, "{}", case.name);
+            }
+            Ok(())
+        });
     }
 }
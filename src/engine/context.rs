@@ -1,31 +1,138 @@
 // SPDX-License-Identifier: MPL-2.0
+use std::collections::HashMap;
+
+use crate::config;
 use crate::model;
+use crate::prelude::*;
 
 #[derive(Debug, PartialEq)]
 pub struct Context {
-    pub date: model::Date,
-    rules: Vec<model::Rule>,
+    now: chrono::DateTime<chrono::Utc>,
+    zone: model::Zone,
+    rules: config::rule::Rules,
+    version: Option<model::Version>,
+    cargo: config::cargo::Package,
+    env: HashMap<String, String>,
 }
 
 impl Context {
-    pub fn load(rules: Vec<impl Into<model::Rule>>) -> Self {
+    pub fn load(
+        rules: config::rule::Rules,
+        version: Option<model::Version>,
+        cargo: config::cargo::Package,
+        zone: model::Zone,
+    ) -> Self {
         Context {
-            date: model::Date::now(),
-            rules: rules.into_iter().map(Into::into).collect(),
+            now: chrono::Utc::now(),
+            zone,
+            rules,
+            version,
+            cargo,
+            env: std::env::vars().collect(),
         }
     }
 
-    pub fn is_triggered(&self, trigger: &model::Trigger) -> bool {
-        match trigger {
-            model::Trigger::Date(date) => date <= &self.date,
-            model::Trigger::Rule(rule) => !self.rules.contains(rule),
+    pub fn is_triggered(&self, trigger: &model::Trigger) -> Result<bool> {
+        Ok(match trigger {
+            model::Trigger::Date(date) => date.instant(&self.zone) <= self.now,
+            model::Trigger::Window(from, until) => {
+                from.instant(&self.zone) <= self.now && self.now < until.instant(&self.zone)
+            }
+            model::Trigger::Rule(rule) => match self.rules.find(rule.name()) {
+                Some((_, definition)) => match definition.state {
+                    config::rule::State::Retired => true,
+                    config::rule::State::Active => definition
+                        .expires
+                        .as_ref()
+                        .is_some_and(|expires| expires.instant(&self.zone) <= self.now),
+                },
+                None => return trace!("Rule `{}` is not declared in .torin.toml", rule.name()),
+            },
+            model::Trigger::Version(requirement) => match self.version()? {
+                Some(version) => version.matches(requirement),
+                None => {
+                    return trace!(
+                        "`version{}` needs a project version; set `version` or `cargo` in .torin.toml or pass --version",
+                        requirement
+                    );
+                }
+            },
+            model::Trigger::Feature(feature) => match self.cargo.get()? {
+                Some(cargo) => !cargo.features.contains(feature),
+                None => return trace!("`feature={}` needs a Cargo.toml", feature),
+            },
+            model::Trigger::Dep(name, requirement) => {
+                let Some(packages) = self.cargo.get()?.and_then(|c| c.packages.as_ref()) else {
+                    return trace!("`dep={}` needs a Cargo.lock", name);
+                };
+                // Cargo treats `-` and `_` in crate names alike.
+                let name = name.replace('-', "_");
+                packages.iter().any(|(package, version)| {
+                    package.replace('-', "_") == name && version.matches(requirement)
+                })
+            }
+            model::Trigger::Env(name, expected) => match (self.env.get(name), expected) {
+                (Some(value), Some(expected)) => value == expected,
+                (Some(value), None) => !value.is_empty(),
+                (None, _) => false,
+            },
+            model::Trigger::Ttl(ttl) => match ttl.expires() {
+                Some(expires) => expires <= self.now,
+                None => return trace!("`{}` was evaluated before its start was known", ttl),
+            },
+            model::Trigger::All(triggers) => {
+                for t in triggers {
+                    if !self.is_triggered(t)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            model::Trigger::Any(triggers) => {
+                for t in triggers {
+                    if self.is_triggered(t)? {
+                        return Ok(true);
+                    }
+                }
+                false
+            }
+            model::Trigger::Not(trigger) => !self.is_triggered(trigger)?,
+            model::Trigger::Always => true,
+        })
+    }
+
+    // `--version` or `version` in .torin.toml, else the version of the Cargo package.
+    fn version(&self) -> Result<Option<model::Version>> {
+        if let Some(version) = &self.version {
+            return Ok(Some(version.clone()));
         }
+        self.cargo
+            .get()?
+            .and_then(|cargo| cargo.version.as_deref())
+            .map(model::Version::try_from)
+            .transpose()
+    }
+
+    pub fn rules(&self) -> &config::rule::Rules {
+        &self.rules
     }
 
     #[cfg(test)]
-    pub fn mock(date: model::Date, rules: Vec<impl Into<model::Rule>>) -> Self {
-        let mut ctx = Context::load(rules);
-        ctx.date = date;
+    pub fn mock(date: model::Date, rules: Vec<(&str, config::rule::State)>) -> Self {
+        let rules = rules
+            .into_iter()
+            .map(|(name, state)| {
+                let rule = config::rule::Rule {
+                    state,
+                    ..config::rule::Rule::active()
+                };
+                (name.to_string(), rule)
+            })
+            .collect();
+        let rules = config::rule::Rules::new(rules).expect("mock rules are valid patterns");
+        let mut ctx = Context::load(rules, None, Default::default(), model::Zone::Utc);
+        ctx.now = date.instant(&model::Zone::Utc);
+        ctx.env = HashMap::new();
         ctx
     }
 }
@@ -33,14 +140,214 @@ impl Context {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use config::rule::State;
 
     #[test]
     fn test_context() {
-        let ctx = Context::mock(model::Date::mock(2025, 5, 20), vec!["foo", "bar"]);
-        assert!(!ctx.is_triggered(&model::Trigger::rule("foo")));
-        assert!(ctx.is_triggered(&model::Trigger::rule("baz")));
-        assert!(ctx.is_triggered(&model::Trigger::Date(model::Date::mock(2025, 5, 19))));
-        assert!(ctx.is_triggered(&model::Trigger::Date(model::Date::mock(2025, 5, 20))));
-        assert!(!ctx.is_triggered(&model::Trigger::Date(model::Date::mock(2025, 5, 21))));
+        testing::with_trace(|| {
+            let mut ctx = Context::mock(
+                model::Date::mock(2025, 5, 20),
+                vec![("foo", State::Active), ("baz", State::Retired)],
+            );
+            assert!(!ctx.is_triggered(&model::Trigger::rule("foo"))?);
+            assert!(ctx.is_triggered(&model::Trigger::rule("baz"))?);
+            assert!(ctx.is_triggered(&model::Trigger::rule("fooo")).is_err());
+            ctx.rules = config::rule::Rules::new(HashMap::from([
+                (
+                    "bar".to_string(),
+                    config::rule::Rule {
+                        expires: Some(model::Date::mock(2025, 5, 20)),
+                        ..config::rule::Rule::active()
+                    },
+                ),
+                (
+                    "payments/*".to_string(),
+                    config::rule::Rule {
+                        state: State::Retired,
+                        ..config::rule::Rule::active()
+                    },
+                ),
+            ]))?;
+            assert!(ctx.is_triggered(&model::Trigger::rule("bar"))?);
+            assert!(ctx.is_triggered(&model::Trigger::rule("payments/legacy_gateway"))?);
+            assert!(ctx
+                .is_triggered(&model::Trigger::rule("payments/card/legacy"))
+                .is_err());
+            assert!(ctx.is_triggered(&model::Trigger::Date(model::Date::mock(2025, 5, 19)))?);
+            assert!(ctx.is_triggered(&model::Trigger::Date(model::Date::mock(2025, 5, 20)))?);
+            assert!(!ctx.is_triggered(&model::Trigger::Date(model::Date::mock(2025, 5, 21)))?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_context_expressions() {
+        testing::with_trace(|| {
+            let mut ctx = Context::mock(
+                model::Date::mock(2025, 5, 20),
+                vec![("foo", State::Active), ("baz", State::Retired)],
+            );
+            ctx.version = Some(model::Version::try_from("2.1.0")?);
+            ctx.cargo = config::cargo::Package::from(config::cargo::Cargo {
+                version: None,
+                features: vec!["tls".to_string()],
+                packages: Some(vec![(
+                    "serde-json".to_string(),
+                    model::Version::try_from("1.0.140")?,
+                )]),
+            });
+            ctx.env = HashMap::from([
+                ("RELEASE_BUILD".to_string(), "1".to_string()),
+                ("DEPLOY_TARGET".to_string(), "staging".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ]);
+            struct Case {
+                expr: &'static str,
+                expected: bool,
+            }
+            for case in [
+                Case {
+                    expr: "date=2025-05-20..2025-05-21",
+                    expected: true,
+                },
+                Case {
+                    expr: "date=2025-05-01..2025-05-20",
+                    expected: false,
+                },
+                Case {
+                    expr: "date=2025-05-21..2025-06-01",
+                    expected: false,
+                },
+                Case {
+                    expr: "all(date=2025-01-01, rule=baz)",
+                    expected: true,
+                },
+                Case {
+                    expr: "all(date=2025-01-01, rule=foo)",
+                    expected: false,
+                },
+                Case {
+                    expr: "any(date=2026-01-01, rule=foo)",
+                    expected: false,
+                },
+                Case {
+                    expr: "any(date=2026-01-01, not(rule=foo))",
+                    expected: true,
+                },
+                Case {
+                    expr: "version>=2.0.0",
+                    expected: true,
+                },
+                Case {
+                    expr: "version==2.2",
+                    expected: false,
+                },
+                Case {
+                    expr: "all(version>=2.0.0, version<3)",
+                    expected: true,
+                },
+                Case {
+                    expr: "feature=tls",
+                    expected: false,
+                },
+                Case {
+                    expr: "feature=legacy",
+                    expected: true,
+                },
+                Case {
+                    expr: "dep=serde_json<2",
+                    expected: true,
+                },
+                Case {
+                    expr: "dep=serde-json>=2",
+                    expected: false,
+                },
+                Case {
+                    expr: "dep=tokio",
+                    expected: false,
+                },
+                Case {
+                    expr: "env=RELEASE_BUILD",
+                    expected: true,
+                },
+                Case {
+                    expr: "env=EMPTY",
+                    expected: false,
+                },
+                Case {
+                    expr: "env=MISSING",
+                    expected: false,
+                },
+                Case {
+                    expr: "env=DEPLOY_TARGET:prod",
+                    expected: false,
+                },
+                Case {
+                    expr: "env=DEPLOY_TARGET:staging",
+                    expected: true,
+                },
+            ] {
+                let trigger = model::Trigger::parse(case.expr).expect(case.expr);
+                assert_eq!(ctx.is_triggered(&trigger)?, case.expected, "{}", case.expr);
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_context_zone() {
+        testing::with_trace(|| {
+            // 2026-01-01 00:30 in Tokyo, still 2025-12-31 in UTC.
+            let mut ctx = Context::mock(model::Date::mock(2025, 12, 31), vec![]);
+            ctx.now = "2025-12-31T15:30:00Z".parse()?;
+            let trigger = model::Trigger::parse("date=2026-01-01").expect("valid date");
+            assert!(!ctx.is_triggered(&trigger)?);
+            ctx.zone = model::Zone::try_from("Asia/Tokyo")?;
+            assert!(ctx.is_triggered(&trigger)?);
+            let trigger = model::Trigger::parse("date=2025-12-31T15:00Z").expect("valid date");
+            assert!(ctx.is_triggered(&trigger)?);
+            let trigger = model::Trigger::parse("date=2026-01-01T01:00+09:00").expect("valid date");
+            assert!(!ctx.is_triggered(&trigger)?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_context_ttl() {
+        testing::with_trace(|| {
+            let ctx = Context::mock(model::Date::mock(2025, 5, 20), vec![]);
+            let mut ttl = model::Ttl::try_from("90d")?;
+            assert!(ctx.is_triggered(&model::Trigger::Ttl(ttl.clone())).is_err());
+            ttl.stamp("2025-02-19T00:00:00Z".parse()?);
+            assert!(ctx.is_triggered(&model::Trigger::Ttl(ttl.clone()))?);
+            ttl.stamp("2025-02-20T00:00:00Z".parse()?);
+            assert!(!ctx.is_triggered(&model::Trigger::Ttl(ttl))?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_context_without_sources() {
+        let ctx = Context::mock(model::Date::mock(2025, 5, 20), vec![]);
+        for expr in ["version>=2.0.0", "feature=tls", "dep=tokio"] {
+            let trigger = model::Trigger::parse(expr).expect(expr);
+            assert!(ctx.is_triggered(&trigger).is_err(), "{expr}");
+        }
+    }
+
+    #[test]
+    fn test_context_cargo_version() {
+        testing::with_trace(|| {
+            let mut ctx = Context::mock(model::Date::mock(2025, 5, 20), vec![]);
+            ctx.cargo = config::cargo::Package::from(config::cargo::Cargo {
+                version: Some("2.1.0".to_string()),
+                ..Default::default()
+            });
+            let trigger = model::Trigger::parse("version>=2.0.0").expect("valid version");
+            assert!(ctx.is_triggered(&trigger)?);
+            ctx.version = Some(model::Version::try_from("1.9.0")?);
+            assert!(!ctx.is_triggered(&trigger)?);
+            Ok(())
+        });
     }
 }
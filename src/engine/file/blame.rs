@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::prelude::*;
+
+// `git blame` reports lines that are not committed yet under this commit.
+const UNCOMMITTED: &str = "0000000000000000000000000000000000000000";
+
+// When each line of a file was last committed. Lines git does not know about fall
+// back to the modification time of the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Blame {
+    committed: Vec<Option<DateTime<Utc>>>,
+    modified: DateTime<Utc>,
+}
+
+impl Blame {
+    pub fn load(path: &str) -> Result<Self> {
+        let modified = std::fs::metadata(path)?.modified()?.into();
+        let output = std::process::Command::new("git")
+            .args(["blame", "--porcelain", "--", path])
+            .output();
+        let committed = match output {
+            Ok(output) if output.status.success() => {
+                Self::parse(&String::from_utf8_lossy(&output.stdout))
+            }
+            // Outside a repository or for untracked files every line is uncommitted.
+            _ => vec![],
+        };
+        Ok(Blame {
+            committed,
+            modified,
+        })
+    }
+
+    #[cfg(test)]
+    pub fn new(committed: Vec<Option<DateTime<Utc>>>, modified: DateTime<Utc>) -> Self {
+        Blame {
+            committed,
+            modified,
+        }
+    }
+
+    pub fn committed(&self, origin: usize) -> DateTime<Utc> {
+        self.committed
+            .get(origin)
+            .copied()
+            .flatten()
+            .unwrap_or(self.modified)
+    }
+
+    // Reads `git blame --porcelain`: each line is introduced by `<sha> <orig> <final>`,
+    // followed by the commit headers the first time a commit appears, and then the
+    // content prefixed with a tab.
+    fn parse(porcelain: &str) -> Vec<Option<DateTime<Utc>>> {
+        let mut times = HashMap::new();
+        let mut committed = vec![];
+        let mut sha = None;
+        for line in porcelain.lines() {
+            if line.starts_with('\t') {
+                committed.push(
+                    sha.take()
+                        .filter(|sha| *sha != UNCOMMITTED)
+                        .and_then(|sha| times.get(sha).copied()),
+                );
+            } else if let Some(time) = line.strip_prefix("committer-time ") {
+                if let (Some(sha), Ok(time)) = (sha, time.parse::<i64>()) {
+                    times.insert(sha, DateTime::from_timestamp(time, 0));
+                }
+            } else if let Some(head) = line.split(' ').next() {
+                if head.len() == UNCOMMITTED.len() && head.chars().all(|c| c.is_ascii_hexdigit()) {
+                    sha = Some(head);
+                }
+            }
+        }
+        committed.into_iter().map(Option::flatten).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        testing::with_trace(|| {
+            let a = "a".repeat(40);
+            let porcelain = format!(
+                "{a} 1 1 2\nauthor Alice\ncommitter-time 1735689600\nsummary init\nfilename src/lib.rs\n\tfn a() {{}}\n\
+                 {a} 2 2\n\tfn b() {{}}\n\
+                 {UNCOMMITTED} 3 3 1\nauthor Not Committed Yet\ncommitter-time 1767225600\nfilename src/lib.rs\n\tfn c() {{}}\n"
+            );
+            let committed = Blame::parse(&porcelain);
+            let init: DateTime<Utc> = "2025-01-01T00:00:00Z".parse()?;
+            assert_eq!(committed, vec![Some(init), Some(init), None]);
+
+            let modified = "2025-06-01T00:00:00Z".parse()?;
+            let blame = Blame::new(committed, modified);
+            assert_eq!(blame.committed(1), init);
+            assert_eq!(blame.committed(2), modified);
+            assert_eq!(blame.committed(3), modified);
+            Ok(())
+        });
+    }
+}
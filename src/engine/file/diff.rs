@@ -1,38 +1,86 @@
 // SPDX-License-Identifier: MPL-2.0
 use crate::model::cutify::*;
 
+#[derive(Clone, Copy, PartialEq)]
+enum Sign {
+    Removed,
+    Added,
+    Context,
+}
+
+impl Sign {
+    fn prefix(&self) -> &'static str {
+        match self {
+            Sign::Removed => "- ",
+            Sign::Added => "+ ",
+            Sign::Context => "  ",
+        }
+    }
+}
+
 pub struct Diff {
     begin: usize,
     end: usize,
-    content: String,
+    inserted: usize,
+    hunk: Vec<(Sign, String)>,
+    removal: bool,
 }
 
 impl Diff {
+    // Marks the diff as removing the whole file.
+    pub fn removal(mut self) -> Self {
+        self.removal = true;
+        self
+    }
+
     pub fn lineno(&self) -> usize {
         self.begin + 1
     }
 
+    pub fn contains(&self, index: usize) -> bool {
+        self.begin <= index && index < self.end
+    }
+
     fn unified_diff_format_header(&self) -> String {
+        if self.removal {
+            return format!("deleted file\n@@ -1,{} +0,0 @@", self.end);
+        }
         format!(
-            "@@ -{},{} +{},0 @@",
+            "@@ -{},{} +{},{} @@",
             self.begin + 1,
             self.end - self.begin,
-            self.begin + 1
+            self.begin + 1,
+            self.inserted
         )
     }
 
     pub fn unified_diff_format(&self) -> String {
-        format!(
-            "{}\n{}",
-            self.unified_diff_format_header().cutify().bold(),
-            self.content.cutify().red()
-        )
+        let mut out = vec![self
+            .unified_diff_format_header()
+            .cutify()
+            .bold()
+            .to_string()];
+        for run in self.hunk.chunk_by(|a, b| a.0 == b.0) {
+            let content = run
+                .iter()
+                .map(|(sign, line)| format!("{}{line}", sign.prefix()))
+                .collect::<Vec<_>>()
+                .join("\n");
+            out.push(match run[0].0 {
+                Sign::Removed => content.cutify().red().to_string(),
+                Sign::Added => content.cutify().green().to_string(),
+                Sign::Context => content,
+            });
+        }
+        out.join("\n")
     }
 }
 
 pub struct DiffBuilder {
     begin: usize,
     lines: Vec<String>,
+    hunk: Vec<(Sign, String)>,
+    pending: Vec<String>,
 }
 
 impl DiffBuilder {
@@ -40,23 +88,44 @@ impl DiffBuilder {
         Self {
             begin,
             lines: vec![],
+            hunk: vec![],
+            pending: vec![],
         }
     }
 
     pub fn add(&mut self, line: String) {
-        self.lines.push(line);
+        self.lines.push(line.clone());
+        self.hunk.push((Sign::Removed, line));
+    }
+
+    // Added lines are shown after the removed lines they replace.
+    pub fn insert(&mut self, line: String) {
+        self.pending.push(line);
+    }
+
+    pub fn keep(&mut self, line: String) {
+        self.flush();
+        self.lines.push(line.clone());
+        self.hunk.push((Sign::Context, line));
+    }
+
+    fn flush(&mut self) {
+        self.hunk
+            .extend(self.pending.drain(..).map(|line| (Sign::Added, line)));
     }
 
-    pub fn build(self) -> Diff {
+    pub fn build(mut self) -> Diff {
+        self.flush();
         Diff {
             begin: self.begin,
             end: self.begin + self.lines.len(),
-            content: self
-                .lines
-                .into_iter()
-                .map(|s| format!("- {s}"))
-                .collect::<Vec<_>>()
-                .join("\n"),
+            inserted: self
+                .hunk
+                .iter()
+                .filter(|(sign, _)| *sign != Sign::Removed)
+                .count(),
+            hunk: self.hunk,
+            removal: false,
         }
     }
 }
@@ -85,15 +154,34 @@ mod tests {
         let diff = builder.build();
         assert_eq!(diff.begin, 0);
         assert_eq!(diff.end, 2);
-        assert_eq!(
-            diff.content,
-            "- This is a test line.\n- This is another line."
-        );
 
         let unified_diff = diff.unified_diff_format();
         assert_eq!(
             unified_diff,
             "\x1b[1;39m@@ -1,2 +1,0 @@\x1b[0m\n\x1b[31m- This is a test line.\n- This is another line.\x1b[0m"
         );
+        let mut builder = DiffBuilder::new(3);
+        builder.add("// legacy()".to_string());
+        builder.insert("legacy()".to_string());
+        assert_eq!(
+            builder.build().unified_diff_format(),
+            "\x1b[1;39m@@ -4,1 +4,1 @@\x1b[0m\n\x1b[31m- // legacy()\x1b[0m\n\x1b[32m+ legacy()\x1b[0m"
+        );
+
+        let mut builder = DiffBuilder::new(0);
+        builder.add("// torin DELETE BEGIN rule=foo".to_string());
+        builder.add("old();".to_string());
+        builder.add("// torin ELSE".to_string());
+        builder.keep("new();".to_string());
+        builder.add("// torin DELETE END".to_string());
+        assert_eq!(
+            builder.build().unified_diff_format(),
+            "\x1b[1;39m@@ -1,5 +1,1 @@\x1b[0m\n\x1b[31m- // torin DELETE BEGIN rule=foo\n- old();\n- // torin ELSE\x1b[0m\n  new();\n\x1b[31m- // torin DELETE END\x1b[0m"
+        );
+
+        assert_eq!(
+            diff.removal().unified_diff_format_header(),
+            "deleted file\n@@ -1,2 +0,0 @@"
+        );
     }
 }
@@ -5,6 +5,7 @@ use super::diff::{Diff, DiffBuilder};
 pub enum Flag {
     NotChange,
     Delete,
+    Replace(String),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -20,16 +21,24 @@ impl Lines {
     pub fn lines(&self) -> Vec<String> {
         self.data
             .iter()
-            .filter_map(|(flag, line)| {
-                if matches!(flag, Flag::Delete) {
-                    None
-                } else {
-                    Some(line.clone())
-                }
+            .filter_map(|(flag, line)| match flag {
+                Flag::NotChange => Some(line.clone()),
+                Flag::Delete => None,
+                Flag::Replace(text) => Some(text.clone()),
             })
             .collect()
     }
 
+    pub fn origin(&self, index: usize) -> usize {
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|(_, (flag, _))| !matches!(flag, Flag::Delete))
+            .nth(index)
+            .map(|(origin, _)| origin)
+            .unwrap_or(self.data.len())
+    }
+
     pub fn flagging(&mut self, flag: Flag, range: std::ops::Range<usize>) {
         let mut seek_index = 0;
         for e in self.data.iter_mut() {
@@ -46,6 +55,17 @@ impl Lines {
         }
     }
 
+    pub fn replace(&mut self, index: usize, text: String) {
+        if let Some(e) = self
+            .data
+            .iter_mut()
+            .filter(|(flag, _)| !matches!(flag, Flag::Delete))
+            .nth(index)
+        {
+            e.0 = Flag::Replace(text);
+        }
+    }
+
     pub fn diffs(&self) -> Vec<Diff> {
         let mut diffs = vec![];
         let mut diff_builder = Option::<DiffBuilder>::None;
@@ -56,23 +76,30 @@ impl Lines {
                         diffs.push(builder.build());
                     }
                 }
-                Flag::Delete => match diff_builder {
-                    Some(ref mut builder) => {
-                        builder.add(line.clone());
-                    }
-                    None => {
-                        let mut builder = DiffBuilder::new(index);
-                        builder.add(line.clone());
-                        diff_builder = Some(builder);
+                Flag::Delete | Flag::Replace(_) => {
+                    let builder = diff_builder.get_or_insert_with(|| DiffBuilder::new(index));
+                    match flag {
+                        Flag::Replace(text) if text == line => builder.keep(line.clone()),
+                        Flag::Replace(text) => {
+                            builder.add(line.clone());
+                            builder.insert(text.clone());
+                        }
+                        _ => builder.add(line.clone()),
                     }
-                },
+                }
             }
         }
+        diffs.extend(diff_builder.map(DiffBuilder::build));
         diffs
     }
 
     pub fn apply(&mut self) {
         self.data.retain(|(flag, _)| !matches!(flag, Flag::Delete));
+        for (flag, line) in self.data.iter_mut() {
+            if let Flag::Replace(text) = std::mem::replace(flag, Flag::NotChange) {
+                *line = text;
+            }
+        }
     }
 
     pub fn join(&self) -> String {
@@ -161,6 +188,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_lines_origin() {
+        let mut lines = Lines::from("line1\nline2\nline3\nline4\nline5".to_string());
+        lines.flagging(Flag::Delete, 1..3);
+        assert_eq!(lines.origin(0), 0);
+        assert_eq!(lines.origin(1), 3);
+        assert_eq!(lines.origin(2), 4);
+        assert_eq!(lines.origin(3), 5);
+    }
+
+    #[test]
+    fn test_lines_diffs_at_end() {
+        let mut lines = Lines::from("line1\nline2\nline3".to_string());
+        lines.flagging(Flag::Delete, 1..3);
+        let diffs = lines.diffs();
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].lineno(), 2);
+    }
+
+    #[test]
+    fn test_lines_replace() {
+        let mut lines = Lines::from("line1\nline2\nline3\nline4".to_string());
+        lines.flagging(Flag::Delete, 0..1);
+        lines.replace(1, "LINE3".to_string());
+        assert_eq!(lines.lines(), vec!["line2", "LINE3", "line4"]);
+        let diffs = lines.diffs();
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(diffs[1].lineno(), 3);
+        lines.apply();
+        assert_eq!(lines.join(), "line2\nLINE3\nline4");
+    }
+
     #[test]
     fn test_lines_apply() {
         let mut lines = Lines::from("line1\nline2\nline3\nline4\nline5".to_string());
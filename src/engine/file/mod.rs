@@ -1,7 +1,9 @@
 // SPDX-License-Identifier: MPL-2.0
+mod blame;
 mod diff;
 mod lines;
 
+use blame::Blame;
 use lines::Lines;
 
 use crate::prelude::*;
@@ -18,6 +20,9 @@ pub enum Destination {
 pub struct File {
     path: Option<String>,
     lines: Lines,
+    removed: bool,
+    // Loaded on the first `committed` call, only files with TTLs need it.
+    blame: Option<Blame>,
 }
 
 impl File {
@@ -29,6 +34,8 @@ impl File {
         Ok(Self {
             path: Some(path.as_ref().to_string()),
             lines: Lines::from(content),
+            removed: false,
+            blame: None,
         })
     }
 
@@ -36,6 +43,27 @@ impl File {
         self.lines.lines()
     }
 
+    pub fn origin(&self, index: usize) -> usize {
+        self.lines.origin(index)
+    }
+
+    // When the line at `origin` in the loaded file was last committed.
+    pub fn committed(&mut self, origin: usize) -> Result<chrono::DateTime<chrono::Utc>> {
+        if self.blame.is_none() {
+            let Some(path) = &self.path else {
+                return trace!(
+                    "Cannot blame without a file path. Use `File::load` to load a file first."
+                );
+            };
+            self.blame = Some(Blame::load(path)?);
+        }
+        Ok(self
+            .blame
+            .as_ref()
+            .map(|blame| blame.committed(origin))
+            .expect("blame is loaded above"))
+    }
+
     pub fn flagging(&mut self, begin: usize, end: usize) {
         let end = if end >= self.lines.len() {
             self.lines.len()
@@ -45,16 +73,51 @@ impl File {
         self.lines.flagging(lines::Flag::Delete, begin..end);
     }
 
+    pub fn replace(&mut self, index: usize, text: String) {
+        self.lines.replace(index, text);
+    }
+
+    // Flags every line and marks the file itself for removal on `dump`.
+    pub fn remove(&mut self) {
+        // A trailing newline leaves an empty last element that is not a line of the file.
+        let len = match self.lines.lines().last() {
+            Some(last) if last.is_empty() => self.lines.len() - 1,
+            _ => self.lines.len(),
+        };
+        self.lines.flagging(lines::Flag::Delete, 0..len);
+        self.removed = true;
+    }
+
     pub fn apply(&mut self) {
         self.lines.apply();
     }
 
     pub fn diffs(&self) -> Vec<diff::Diff> {
-        self.lines.diffs()
+        let diffs = self.lines.diffs();
+        if self.removed {
+            return diffs.into_iter().map(diff::Diff::removal).collect();
+        }
+        diffs
     }
 
     pub fn dump(&self, dest: Destination) -> Result<String> {
         let contents = self.lines.join();
+        if self.removed {
+            match (&self.path, dest) {
+                (Some(path), Destination::Overwrite) => std::fs::remove_file(path)?,
+                (None, Destination::Overwrite) => trace!(
+                    "Cannot remove without a file path. Use `File::load` to load a file first."
+                )?,
+                (_, Destination::File(path)) => {
+                    if std::path::Path::new(&path).exists() {
+                        std::fs::remove_file(path)?;
+                    }
+                }
+                #[cfg(test)]
+                (_, Destination::Noop) => {}
+            }
+            return Ok(contents);
+        }
         match (&self.path, dest) {
             (Some(path), Destination::Overwrite) => {
                 std::fs::write(path, &contents)?;
@@ -77,8 +140,20 @@ impl File {
         Self {
             path: None,
             lines: Lines::from(lines),
+            removed: false,
+            blame: None,
         }
     }
+
+    #[cfg(test)]
+    pub fn mock_blame(
+        mut self,
+        committed: Vec<Option<chrono::DateTime<chrono::Utc>>>,
+        modified: chrono::DateTime<chrono::Utc>,
+    ) -> Self {
+        self.blame = Some(Blame::new(committed, modified));
+        self
+    }
 }
 
 #[cfg(test)]
@@ -125,6 +200,25 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_dump_removed() {
+        testing::with_trace(|| {
+            let path = std::env::temp_dir().join(format!("torin-remove-{}.rs", std::process::id()));
+            let path = path.to_string_lossy().to_string();
+            std::fs::write(&path, "// torin DELETE FILE rule=foo\nfn main() {}\n")?;
+            let mut f = File::load(&path)?;
+            f.remove();
+            assert_eq!(f.diffs().len(), 1);
+            assert!(f.diffs()[0]
+                .unified_diff_format()
+                .contains("@@ -1,2 +0,0 @@"));
+            f.apply();
+            f.dump(Destination::Overwrite)?;
+            assert!(!std::path::Path::new(&path).exists());
+            Ok(())
+        })
+    }
+
     #[test]
     fn test_drain() {
         let f = File::mock(vec![
@@ -0,0 +1,183 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::collections::BTreeMap;
+
+use sha2::{Digest, Sha256};
+
+use super::plan;
+use crate::prelude::*;
+
+const PATH: &str = ".torin.lock";
+
+const HEADER: &str = "# Generated by `torin lock`. Fingerprints of REQUIRE blocks.\n\n";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Requirement {
+    pub id: String,
+    pub origin: usize,
+    pub hash: String,
+}
+
+impl Requirement {
+    // Fingerprints the body of a REQUIRE block, excluding its markers.
+    pub fn new(plan: &plan::Plan, lines: &[String], origin: usize) -> Self {
+        let body = lines
+            .get(plan.begin() + 1..plan.end())
+            .unwrap_or_default()
+            .join("\n");
+        Requirement {
+            id: plan.id().unwrap_or_default().to_string(),
+            origin,
+            hash: format!("{:x}", Sha256::digest(body.as_bytes())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    Missing(String),
+    Changed(Requirement),
+    Unlocked(Requirement),
+}
+
+impl Violation {
+    // The line to point at, if the block still exists.
+    pub fn origin(&self) -> Option<usize> {
+        match self {
+            Violation::Missing(_) => None,
+            Violation::Changed(r) | Violation::Unlocked(r) => Some(r.origin),
+        }
+    }
+
+    // Whether the violation means a guarded block would be lost or altered.
+    pub fn is_breach(&self) -> bool {
+        !matches!(self, Violation::Unlocked(_))
+    }
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::Missing(id) => write!(f, "required block `{id}` is missing"),
+            Violation::Changed(r) => write!(
+                f,
+                "required block `{}` changed; run `torin lock` if this is intended",
+                r.id
+            ),
+            Violation::Unlocked(r) => write!(
+                f,
+                "required block `{}` is not locked; run `torin lock`",
+                r.id
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Lock {
+    #[serde(default)]
+    require: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl Lock {
+    pub fn load() -> Result<Self> {
+        match std::fs::read_to_string(PATH) {
+            Ok(content) => Ok(toml::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Lock::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        std::fs::write(PATH, format!("{HEADER}{}", toml::to_string(self)?))?;
+        Ok(())
+    }
+
+    pub fn insert(&mut self, path: &str, requirement: Requirement) {
+        self.require
+            .entry(path.to_string())
+            .or_default()
+            .insert(requirement.id, requirement.hash);
+    }
+
+    pub fn len(&self) -> usize {
+        self.require.values().map(BTreeMap::len).sum()
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &String> {
+        self.require.keys()
+    }
+
+    pub fn verify(&self, path: &str, found: &[Requirement]) -> Vec<Violation> {
+        let locked = self.require.get(path);
+        let mut violations = found
+            .iter()
+            .filter_map(|r| match locked.and_then(|locked| locked.get(&r.id)) {
+                Some(hash) if *hash == r.hash => None,
+                Some(_) => Some(Violation::Changed(r.clone())),
+                None => Some(Violation::Unlocked(r.clone())),
+            })
+            .collect::<Vec<_>>();
+        violations.extend(
+            locked
+                .into_iter()
+                .flatten()
+                .filter(|(id, _)| found.iter().all(|r| r.id != **id))
+                .map(|(id, _)| Violation::Missing(id.clone())),
+        );
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn requirement(id: &str, origin: usize, hash: &str) -> Requirement {
+        Requirement {
+            id: id.to_string(),
+            origin,
+            hash: hash.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let mut lock = Lock::default();
+        lock.insert("src/audit.rs", requirement("audit", 0, "aaa"));
+        lock.insert("src/audit.rs", requirement("license", 0, "bbb"));
+        assert_eq!(lock.len(), 2);
+        let found = [
+            requirement("audit", 3, "aaa"),
+            requirement("license", 10, "ccc"),
+            requirement("retention", 20, "ddd"),
+        ];
+        assert_eq!(
+            lock.verify("src/audit.rs", &found),
+            vec![
+                Violation::Changed(requirement("license", 10, "ccc")),
+                Violation::Unlocked(requirement("retention", 20, "ddd")),
+            ]
+        );
+        assert_eq!(
+            lock.verify("src/audit.rs", &found[1..]),
+            vec![
+                Violation::Changed(requirement("license", 10, "ccc")),
+                Violation::Unlocked(requirement("retention", 20, "ddd")),
+                Violation::Missing("audit".to_string()),
+            ]
+        );
+        assert!(lock.verify("src/other.rs", &[]).is_empty());
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        testing::with_trace(|| {
+            let mut lock = Lock::default();
+            lock.insert("src/audit.rs", requirement("audit", 0, "aaa"));
+            let content = toml::to_string(&lock)?;
+            assert_eq!(content, "[require.\"src/audit.rs\"]\naudit = \"aaa\"\n");
+            assert_eq!(toml::from_str::<Lock>(&content)?, lock);
+            Ok(())
+        });
+    }
+}
@@ -2,39 +2,152 @@
 pub mod action;
 mod context;
 mod file;
+mod lock;
 mod mode;
 mod plan;
 mod status;
+mod summary;
+
+use std::collections::HashMap;
 
 use crate::config;
+use crate::model;
+use crate::model::cutify::CutifyOps;
 use crate::prelude::*;
 pub use status::Status;
+use summary::Summary;
 
 pub struct Engine {
     mode: mode::Mode,
+    deny_warnings: bool,
+    verbose: bool,
     ctx: context::Context,
+    settings: config::annotation::Settings,
+    groups: HashMap<String, model::Group>,
+    languages: config::language::Languages,
     sources: Vec<String>,
 }
 
 impl Engine {
     pub fn init(cli: config::cli::Cli) -> Result<Self> {
+        let deny_warnings = matches!(
+            cli.mode,
+            config::cli::Mode::Check {
+                deny_warnings: true,
+                ..
+            }
+        );
+        let options = cli.mode.options();
         let mode = cli.mode.try_into()?;
         let manifest = config::manifest::Manifest::load()?;
         let sources = manifest.sources()?;
-        let ctx = context::Context::load(manifest.project.rules);
-        Ok(Self { mode, ctx, sources })
+        let languages = config::language::Languages::new(&manifest.languages);
+        let cargo = manifest.cargo();
+        let version = options
+            .version
+            .or_else(|| manifest.project.version.clone())
+            .map(|version| model::Version::try_from(version.as_str()))
+            .transpose()?;
+        let zone = match &manifest.project.timezone {
+            Some(timezone) => model::Zone::try_from(timezone.as_str())?,
+            None => model::Zone::default(),
+        };
+        let rules = manifest.rules()?;
+        let groups = manifest.groups(&rules)?;
+        let ctx = context::Context::load(rules, version, cargo, zone);
+        Ok(Self {
+            mode,
+            deny_warnings,
+            verbose: options.verbose,
+            ctx,
+            settings: manifest.annotation,
+            groups,
+            languages,
+            sources,
+        })
     }
 
     pub fn run(&self) -> Result<Status> {
-        let mut status = Status::Success;
+        if self.mode == mode::Mode::Lock {
+            return self.lock();
+        }
+        let lock = lock::Lock::load()?;
+        let mut summary = Summary::default();
+        for source in &self.sources {
+            let parser = self.parser(source)?;
+            summary += action::Action::new(self.mode).run(&self.ctx, &parser, source, &lock)?;
+        }
+        if self.verbose {
+            self.print_rule_matches(&summary.references);
+        }
+        if self.mode != mode::Mode::Apply {
+            for path in lock.paths().filter(|path| !self.sources.contains(path)) {
+                let violations = lock.verify(path, &[]);
+                action::print_violations(path, &violations);
+                summary.errors += violations.len();
+            }
+        }
+        println!("{} {summary}", format!("{}:", self.mode).cutify().bold());
+        Ok(summary.status(self.mode, self.deny_warnings))
+    }
+
+    // Records the current fingerprint of every REQUIRE block.
+    fn lock(&self) -> Result<Status> {
+        let mut lock = lock::Lock::default();
+        let mut summary = Summary::default();
         for source in &self.sources {
-            match action::Action::new(self.mode).run(&self.ctx, source)? {
-                Status::Success => {}
-                Status::Failure => {
-                    status = Status::Failure;
+            let parser = self.parser(source)?;
+            let lines = file::File::load(source)?.lines();
+            match plan::Plans::parse(&parser, &lines) {
+                Ok(plans) => {
+                    for p in plans.iter().flat_map(plan::Plans::iter) {
+                        if matches!(p.command(), model::Command::Require) {
+                            lock.insert(source, lock::Requirement::new(p, &lines, p.begin()));
+                        }
+                    }
+                }
+                Err(diagnostics) => {
+                    eprintln!("{}\n", diagnostics.render(source));
+                    summary.invalid += 1;
                 }
             }
         }
-        Ok(status)
+        let mut report = summary::count(lock.len(), "required block");
+        if summary.invalid == 0 {
+            lock.save()?;
+        } else {
+            report += &format!(
+                ", {} (lock not written)",
+                summary::count(summary.invalid, "invalid file")
+            );
+        }
+        println!("{} {report}", "lock:".cutify().bold());
+        Ok(summary.status(self.mode, false))
+    }
+
+    fn print_rule_matches(&self, matches: &[(String, String)]) {
+        for (pattern, rule) in self.ctx.rules().iter() {
+            let locations = matches
+                .iter()
+                .filter(|(matched, _)| matched == pattern)
+                .map(|(_, location)| location)
+                .collect::<Vec<_>>();
+            println!(
+                "{} {pattern} {rule}: {}",
+                "rule:".cutify().bold(),
+                summary::count(locations.len(), "annotation")
+            );
+            for location in locations {
+                println!("  {location}");
+            }
+        }
+    }
+
+    fn parser(&self, source: &str) -> Result<config::annotation::Parser> {
+        Ok(
+            config::annotation::Parser::new(&self.languages.syntax(source), &self.settings)?
+                .rules(self.ctx.rules().clone())
+                .groups(self.groups.clone()),
+        )
     }
 }
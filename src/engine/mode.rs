@@ -9,6 +9,7 @@ pub enum Mode {
     Plan,
     Check,
     Apply,
+    Lock,
 }
 
 impl fmt::Display for Mode {
@@ -17,6 +18,7 @@ impl fmt::Display for Mode {
             Self::Plan => write!(f, "plan"),
             Self::Check => write!(f, "check"),
             Self::Apply => write!(f, "apply"),
+            Self::Lock => write!(f, "lock"),
         }
     }
 }
@@ -25,9 +27,10 @@ impl TryFrom<config::cli::Mode> for Mode {
     type Error = Error;
     fn try_from(mode: config::cli::Mode) -> Result<Self> {
         match mode {
-            config::cli::Mode::Plan => Ok(Mode::Plan),
-            config::cli::Mode::Check => Ok(Mode::Check),
-            config::cli::Mode::Apply => Ok(Mode::Apply),
+            config::cli::Mode::Plan { .. } => Ok(Mode::Plan),
+            config::cli::Mode::Check { .. } => Ok(Mode::Check),
+            config::cli::Mode::Apply { .. } => Ok(Mode::Apply),
+            config::cli::Mode::Lock => Ok(Mode::Lock),
             config::cli::Mode::Completion { .. } => {
                 // desired unreachable
                 Err(Error::new("Completion mode is not supported"))
@@ -0,0 +1,466 @@
+// SPDX-License-Identifier: MPL-2.0
+use proc_macro2::TokenTree;
+use quote::ToTokens;
+
+use super::Range;
+use crate::config::language::{Blocks, Syntax, CHAR_LITERAL};
+
+const CONTINUATIONS: [&str; 5] = ["else", "catch", "finally", "while", "."];
+
+// Whether `rest` starts with a continuation such as `else`, as a whole word.
+fn continues(rest: &str) -> bool {
+    CONTINUATIONS.iter().any(|word| {
+        rest.strip_prefix(word).is_some_and(|after| {
+            *word == "."
+                || !after
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_alphanumeric() || c == '_')
+        })
+    })
+}
+
+// Consumes the remaining `n` characters of a token whose first one was just read.
+fn skip(chars: &mut std::str::CharIndices, token: &str) {
+    chars
+        .by_ref()
+        .take(token.chars().count().saturating_sub(1))
+        .for_each(drop);
+}
+
+enum State {
+    Code,
+    Str(char),
+    BlockComment,
+}
+
+// Finds the last line of the brace-delimited item starting at `start`. Braces inside
+// strings, character literals and comments are ignored; outside Rust, `'` delimits
+// strings. An item also ends at a `;`
+// outside any bracket, which covers declarations like `use foo;`.
+pub fn braces(lines: &[String], start: usize, syntax: &Syntax) -> Option<usize> {
+    let (line_comment, block_comment) = match (&syntax.suffix, syntax.prefix.as_str()) {
+        (Some(suffix), prefix) => (None, Some((prefix, suffix.as_str()))),
+        (None, "//") => (Some("//"), Some(("/*", "*/"))),
+        (None, prefix) => (Some(prefix), None),
+    };
+
+    let mut state = State::Code;
+    let mut depth = 0usize;
+    let mut opened = false;
+    let mut closed = None;
+    for (index, line) in lines.iter().enumerate().skip(start) {
+        let mut chars = line.char_indices();
+        while let Some((i, c)) = chars.next() {
+            let rest = &line[i..];
+            match state {
+                State::Str(delimiter) => match c {
+                    '\\' => {
+                        chars.next();
+                    }
+                    c if c == delimiter => state = State::Code,
+                    _ => {}
+                },
+                State::BlockComment => {
+                    if let Some((_, close)) = block_comment {
+                        if rest.starts_with(close) {
+                            skip(&mut chars, close);
+                            state = State::Code;
+                        }
+                    }
+                }
+                State::Code => {
+                    if c.is_whitespace() {
+                        continue;
+                    }
+                    if line_comment.is_some_and(|prefix| rest.starts_with(prefix)) {
+                        break;
+                    }
+                    if let Some((open, _)) =
+                        block_comment.filter(|(open, _)| rest.starts_with(open))
+                    {
+                        skip(&mut chars, open);
+                        state = State::BlockComment;
+                        continue;
+                    }
+                    if let Some(end) = closed {
+                        if continues(rest) {
+                            closed = None;
+                        } else if c == ';' || c == ',' {
+                            return Some(index);
+                        } else {
+                            return Some(end);
+                        }
+                    }
+                    match c {
+                        '"' | '`' => state = State::Str(c),
+                        '\'' if syntax.blocks != Blocks::Rust => state = State::Str(c),
+                        '\'' => {
+                            if let Some(literal) = CHAR_LITERAL.find(rest) {
+                                skip(&mut chars, literal.as_str());
+                            }
+                        }
+                        '{' | '(' | '[' => {
+                            depth += 1;
+                            opened |= c == '{';
+                        }
+                        '}' | ')' | ']' => {
+                            depth = depth.saturating_sub(1);
+                            if depth == 0 && c == '}' && opened {
+                                closed = Some(index);
+                            }
+                        }
+                        ';' if depth == 0 => return Some(index),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+    closed
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+// Finds the last line of the indentation-delimited item starting at `start`: the first
+// code line plus every following line indented deeper than it. Decorators and closing
+// brackets on the base level are kept with the item.
+pub fn indent(lines: &[String], start: usize) -> Option<usize> {
+    let (first, head) = lines
+        .iter()
+        .enumerate()
+        .skip(start)
+        .find(|(_, line)| !line.trim().is_empty())?;
+    let base = indentation(head);
+    let mut decorated = head.trim_start().starts_with('@');
+    let mut end = first;
+    for (index, line) in lines.iter().enumerate().skip(first + 1) {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let level = indentation(line);
+        if level > base || (level == base && trimmed.starts_with([')', ']', '}'])) {
+            end = index;
+        } else if level == base && decorated {
+            end = index;
+            decorated = trimmed.starts_with('@');
+        } else {
+            break;
+        }
+    }
+    Some(end)
+}
+
+pub fn parse(lines: &[String]) -> Option<syn::File> {
+    syn::parse_file(&lines.join("\n")).ok()
+}
+
+// Zero-based lines of a Rust item: `start` includes doc comments and attributes,
+// `head` is the first line after them.
+struct Node {
+    start: usize,
+    head: usize,
+    end: usize,
+}
+
+fn node<T: ToTokens>(item: &T) -> Option<Node> {
+    let tokens = item.to_token_stream().into_iter().collect::<Vec<_>>();
+    let start = tokens.first()?.span().start().line;
+    let end = tokens.last()?.span().end().line;
+    let mut rest = tokens.iter();
+    let head = loop {
+        match rest.next()? {
+            TokenTree::Punct(p) if p.as_char() == '#' => {
+                rest.next();
+            }
+            token => break token.span().start().line,
+        }
+    };
+    Some(Node {
+        start: start - 1,
+        head: head - 1,
+        end: end - 1,
+    })
+}
+
+fn collect(items: &[syn::Item], nodes: &mut Vec<Node>) {
+    for item in items {
+        nodes.extend(node(item));
+        match item {
+            syn::Item::Mod(m) => {
+                if let Some((_, items)) = &m.content {
+                    collect(items, nodes);
+                }
+            }
+            syn::Item::Impl(i) => {
+                for item in &i.items {
+                    nodes.extend(node(item));
+                    if let syn::ImplItem::Fn(f) = item {
+                        statements(&f.block.stmts, nodes);
+                    }
+                }
+            }
+            syn::Item::Trait(t) => {
+                for item in &t.items {
+                    nodes.extend(node(item));
+                }
+            }
+            syn::Item::Fn(f) => statements(&f.block.stmts, nodes),
+            _ => {}
+        }
+    }
+}
+
+fn statements(stmts: &[syn::Stmt], nodes: &mut Vec<Node>) {
+    for stmt in stmts {
+        if let syn::Stmt::Item(item) = stmt {
+            collect(std::slice::from_ref(item), nodes);
+        }
+    }
+}
+
+// Resolves the Rust item annotated at `index`, including its doc comments and
+// attributes even when they precede the annotation. Returns `None` when no item
+// follows, e.g. for statements inside a function body.
+pub fn rust(file: &syn::File, lines: &[String], index: usize) -> Option<Range> {
+    let mut nodes = vec![];
+    collect(&file.items, &mut nodes);
+    let comments = |from: usize, to: usize| {
+        lines[from..to].iter().all(|line| {
+            let line = line.trim();
+            line.is_empty() || line.starts_with("//")
+        })
+    };
+    nodes
+        .into_iter()
+        .find(|n| {
+            (n.start <= index && index < n.head)
+                || (index < n.start && comments(index + 1, n.start))
+        })
+        .map(|n| Range {
+            begin: n.start.min(index),
+            end: n.end,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(src: &str) -> Vec<String> {
+        src.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_braces() {
+        struct Case {
+            name: &'static str,
+            src: &'static str,
+            expected: Option<usize>,
+        }
+        for case in [
+            Case {
+                name: "function with blank lines",
+                src: "fn foo() {\n    let a = 1;\n\n    a\n}\nfn bar() {}",
+                expected: Some(4),
+            },
+            Case {
+                name: "braces in strings and comments",
+                src: "fn foo() {\n    let s = \"}\";\n    let c = '}';\n    // }\n    /* { */\n}\nrest",
+                expected: Some(5),
+            },
+            Case {
+                name: "lifetimes",
+                src: "struct A<'a> {\n    x: &'a str,\n}\nrest",
+                expected: Some(2),
+            },
+            Case {
+                name: "else chain",
+                src: "if a {\n    b();\n} else {\n    c();\n}\nrest",
+                expected: Some(4),
+            },
+            Case {
+                name: "trailing semicolon",
+                src: "let f = |x| {\n    x\n}\n;\nrest",
+                expected: Some(3),
+            },
+            Case {
+                name: "statement",
+                src: "use foo::bar;\nrest",
+                expected: Some(0),
+            },
+            Case {
+                name: "multi-line call",
+                src: "foo(\n    a,\n    b,\n);\nrest",
+                expected: Some(3),
+            },
+            Case {
+                name: "unterminated",
+                src: "fn foo() {\n    a\n",
+                expected: None,
+            },
+        ] {
+            let syntax = Syntax::default().with_blocks(Blocks::Rust);
+            assert_eq!(
+                braces(&lines(case.src), 0, &syntax),
+                case.expected,
+                "{}",
+                case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_braces_other_syntaxes() {
+        struct Case {
+            name: &'static str,
+            src: &'static str,
+            syntax: Syntax,
+            expected: Option<usize>,
+        }
+        for case in [
+            Case {
+                name: "identifier starting with a continuation",
+                src: "if (a) {\n  b();\n}\nelsewhere();\nwhileLoop();",
+                syntax: Syntax::default(),
+                expected: Some(2),
+            },
+            Case {
+                name: "else chain",
+                src: "if (a) {\n  b();\n} else {\n  c();\n}\nrest",
+                syntax: Syntax::default(),
+                expected: Some(4),
+            },
+            Case {
+                name: "single-quoted strings",
+                src: "function f() {\n  const s = '}';\n  const t = 'it\\'s {';\n}\nrest",
+                syntax: Syntax::default(),
+                expected: Some(3),
+            },
+            Case {
+                name: "shell single-quoted strings",
+                src: "f() {\n  echo '}'\n}\nrest",
+                syntax: Syntax::line("#"),
+                expected: Some(2),
+            },
+            Case {
+                name: "one-character block comments",
+                src: "{ note } x := f(\n  { ( } a\n);\nrest",
+                syntax: Syntax::block("{", "}"),
+                expected: Some(2),
+            },
+        ] {
+            assert_eq!(
+                braces(&lines(case.src), 0, &case.syntax),
+                case.expected,
+                "{}",
+                case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_braces_hash_comments() {
+        let src = lines("function f() {\n  # }\n  echo \"{\"\n}\nrest");
+        assert_eq!(braces(&src, 0, &Syntax::line("#")), Some(3));
+    }
+
+    #[test]
+    fn test_rust() {
+        struct Case {
+            name: &'static str,
+            src: &'static str,
+            index: usize,
+            expected: Option<(usize, usize)>,
+        }
+        for case in [
+            Case {
+                name: "doc comments and attributes",
+                src: "// mark\n/// Docs\n#[derive(\n    Debug,\n)]\nstruct A {\n    s: &'static str,\n}\nfn b() {}",
+                index: 0,
+                expected: Some((0, 7)),
+            },
+            Case {
+                name: "annotation between docs and item",
+                src: "/// Docs\n#[inline]\n// mark\nfn a() {\n    let s = \"}\";\n}\nfn b() {}",
+                index: 2,
+                expected: Some((0, 5)),
+            },
+            Case {
+                name: "impl method",
+                src: "impl A {\n    fn a() {}\n\n    // mark\n    /// Docs\n    fn b() {\n\n    }\n}",
+                index: 3,
+                expected: Some((3, 7)),
+            },
+            Case {
+                name: "whole module",
+                src: "// mark\n\nmod a {\n    fn b() {}\n}\nfn c() {}",
+                index: 0,
+                expected: Some((0, 4)),
+            },
+            Case {
+                name: "statement",
+                src: "fn a() {\n    // mark\n    let b = 1;\n}",
+                index: 1,
+                expected: None,
+            },
+            Case {
+                name: "code before item",
+                src: "// mark\nconst A: u8 = 1;\nfn b() {}",
+                index: 0,
+                expected: Some((0, 1)),
+            },
+        ] {
+            let lines = lines(case.src);
+            let file = parse(&lines).expect(case.name);
+            let got = rust(&file, &lines, case.index).map(|r| (r.begin, r.end));
+            assert_eq!(got, case.expected, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_indent() {
+        struct Case {
+            name: &'static str,
+            src: &'static str,
+            expected: Option<usize>,
+        }
+        for case in [
+            Case {
+                name: "python function",
+                src: "def foo():\n    a = 1\n\n    return a\n\ndef bar():\n    pass",
+                expected: Some(3),
+            },
+            Case {
+                name: "decorated",
+                src: "@cache\n@trace\ndef foo():\n    pass\nrest = 1",
+                expected: Some(3),
+            },
+            Case {
+                name: "closing bracket",
+                src: "x = foo(\n    a,\n)\ny = 1",
+                expected: Some(2),
+            },
+            Case {
+                name: "yaml mapping",
+                src: "feature:\n  enabled: true\n  items:\n    - a\nnext: 1",
+                expected: Some(3),
+            },
+            Case {
+                name: "nested start",
+                src: "  - name: a\n    value: b\n  - name: c",
+                expected: Some(1),
+            },
+            Case {
+                name: "empty",
+                src: "\n\n",
+                expected: None,
+            },
+        ] {
+            assert_eq!(indent(&lines(case.src), 0), case.expected, "{}", case.name);
+        }
+    }
+}
@@ -0,0 +1,1055 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::collections::HashMap;
+
+use crate::config::language::Blocks;
+use crate::diagnostic::{Code, Diagnostic, Diagnostics, Span};
+use crate::prelude::*;
+use crate::{config, model};
+
+use super::context;
+
+mod item;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Range {
+    pub begin: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    command: model::Command,
+    trigger: model::Trigger,
+    meta: model::Meta,
+    range: Range,
+    markers: Vec<usize>,
+    file: bool,
+    otherwise: Option<usize>,
+    id: Option<String>,
+}
+
+impl Plan {
+    pub fn command(&self) -> &model::Command {
+        &self.command
+    }
+
+    pub fn trigger(&self) -> &model::Trigger {
+        &self.trigger
+    }
+
+    pub fn meta(&self) -> &model::Meta {
+        &self.meta
+    }
+
+    pub fn begin(&self) -> usize {
+        self.range.begin
+    }
+
+    pub fn end(&self) -> usize {
+        self.range.end
+    }
+
+    pub fn markers(&self) -> &[usize] {
+        &self.markers
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.file
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    // The `ELSE` marker of a switch-over block.
+    pub fn otherwise(&self) -> Option<usize> {
+        self.otherwise
+    }
+}
+
+struct Block {
+    begin: usize,
+    otherwise: Option<usize>,
+    id: Option<String>,
+    command: model::Command,
+    trigger: model::Trigger,
+    meta: model::Meta,
+}
+
+impl Block {
+    fn close(self, end: usize) -> Plan {
+        Plan {
+            command: self.command,
+            trigger: self.trigger,
+            meta: self.meta,
+            range: Range {
+                begin: self.begin,
+                end,
+            },
+            markers: [Some(self.begin), self.otherwise, Some(end)]
+                .into_iter()
+                .flatten()
+                .collect(),
+            file: false,
+            otherwise: self.otherwise,
+            id: self.id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plans {
+    plans: Vec<Plan>,
+}
+
+impl Plans {
+    pub fn parse(
+        parser: &config::annotation::Parser,
+        lines: &[String],
+    ) -> std::result::Result<Option<Self>, Diagnostics> {
+        let mut plans = vec![];
+        let mut diagnostics = vec![];
+        let mut unpaired = vec![];
+        let mut blocks = vec![];
+        let mut labeled: Vec<(String, Block)> = vec![];
+        let mut ids = HashMap::new();
+        let mut ast = None;
+        let annotations = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| parser.is_match(line))
+            .map(|(index, line)| (index, line, parser.parse(line)))
+            .collect::<Vec<_>>();
+        // `NEXT` and `PREV` stop short of block markers so they never cut into a block.
+        let boundaries = annotations
+            .iter()
+            .filter(|(_, _, cfg)| {
+                cfg.as_ref().is_ok_and(|cfg| {
+                    matches!(
+                        cfg.target,
+                        model::Target::Begin(..) | model::Target::End(_) | model::Target::Else(_)
+                    )
+                })
+            })
+            .map(|(index, _, _)| *index)
+            .collect::<Vec<_>>();
+        for (index, line, cfg) in annotations {
+            let cfg = match cfg {
+                Ok(cfg) => cfg,
+                Err(d) => {
+                    diagnostics.push(d.at(index, line));
+                    continue;
+                }
+            };
+            match (cfg.command, cfg.target) {
+                (Some(command), model::Target::Begin(trigger, id)) => {
+                    let block = Block {
+                        begin: index,
+                        otherwise: None,
+                        id: id.clone(),
+                        command,
+                        trigger,
+                        meta: cfg.meta,
+                    };
+                    let Some(id) = id else {
+                        blocks.push(block);
+                        continue;
+                    };
+                    match ids.get(&id) {
+                        Some(first) => unpaired.push(
+                            Diagnostic::new(
+                                Code::DuplicateId,
+                                whole(line),
+                                format!("id `{id}` is already used at line {}", first + 1),
+                            )
+                            .at(index, line),
+                        ),
+                        None => {
+                            ids.insert(id.clone(), index);
+                            labeled.push((id, block));
+                        }
+                    }
+                }
+                (_, model::Target::End(Some(id))) => {
+                    match labeled.iter().position(|(open, _)| *open == id) {
+                        Some(position) => plans.push(labeled.remove(position).1.close(index)),
+                        None => unpaired.push(
+                            Diagnostic::new(
+                                Code::MismatchedId,
+                                whole(line),
+                                format!("`END id={id}` has no matching `BEGIN id={id}`"),
+                            )
+                            .suggest(&id, labeled.iter().map(|(open, _)| open.as_str()), |open| {
+                                format!("id={open}")
+                            })
+                            .at(index, line),
+                        ),
+                    }
+                }
+                (_, model::Target::End(None)) => match blocks.pop() {
+                    Some(block) => plans.push(block.close(index)),
+                    None if !labeled.is_empty() => unpaired.push(
+                        Diagnostic::new(
+                            Code::MismatchedId,
+                            whole(line),
+                            "`END` has no matching unlabeled `BEGIN`",
+                        )
+                        .help(format!(
+                            "labeled blocks are closed with `END id=...` (open: {})",
+                            labeled
+                                .iter()
+                                .map(|(open, _)| format!("`{open}`"))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ))
+                        .at(index, line),
+                    ),
+                    None => unpaired.push(
+                        Diagnostic::new(
+                            Code::UnexpectedAnnotation,
+                            whole(line),
+                            "`END` has no matching `BEGIN`",
+                        )
+                        .at(index, line),
+                    ),
+                },
+                (_, model::Target::Else(id)) => {
+                    let block = match &id {
+                        Some(id) => labeled
+                            .iter_mut()
+                            .find(|(open, _)| open == id)
+                            .map(|(_, block)| block),
+                        None => blocks.last_mut(),
+                    };
+                    let message = match block {
+                        Some(block) if block.otherwise.is_some() => format!(
+                            "the block at line {} already has an `ELSE`",
+                            block.begin + 1
+                        ),
+                        Some(block) if !matches!(block.command, model::Command::Delete) => {
+                            "`ELSE` is only supported in `DELETE` blocks".to_string()
+                        }
+                        Some(block) => {
+                            block.otherwise = Some(index);
+                            continue;
+                        }
+                        None => "`ELSE` has no enclosing `BEGIN`".to_string(),
+                    };
+                    unpaired.push(
+                        Diagnostic::new(Code::UnexpectedAnnotation, whole(line), message)
+                            .at(index, line),
+                    );
+                }
+                (Some(command), model::Target::Neighbor(trigger)) => plans.push(Plan {
+                    command,
+                    trigger,
+                    meta: cfg.meta,
+                    range: neighbor(lines, index),
+                    markers: vec![index],
+                    file: false,
+                    otherwise: None,
+                    id: None,
+                }),
+                (Some(command), model::Target::Line(trigger)) => plans.push(Plan {
+                    command,
+                    trigger,
+                    meta: cfg.meta,
+                    range: Range {
+                        begin: index,
+                        end: index,
+                    },
+                    markers: vec![index],
+                    file: false,
+                    otherwise: None,
+                    id: None,
+                }),
+                (Some(command), model::Target::Next(trigger, count)) => {
+                    let after = lines.iter().enumerate().skip(index + 1);
+                    let Some(end) = neighbors(parser, &boundaries, after, count) else {
+                        diagnostics.push(too_few(line, "NEXT", "after", count).at(index, line));
+                        continue;
+                    };
+                    plans.push(Plan {
+                        command,
+                        trigger,
+                        meta: cfg.meta,
+                        range: Range { begin: index, end },
+                        markers: vec![index],
+                        file: false,
+                        otherwise: None,
+                        id: None,
+                    })
+                }
+                (Some(command), model::Target::Prev(trigger, count)) => {
+                    let before = lines.iter().enumerate().take(index).rev();
+                    let Some(begin) = neighbors(parser, &boundaries, before, count) else {
+                        diagnostics.push(too_few(line, "PREV", "before", count).at(index, line));
+                        continue;
+                    };
+                    plans.push(Plan {
+                        command,
+                        trigger,
+                        meta: cfg.meta,
+                        range: Range { begin, end: index },
+                        markers: vec![index],
+                        file: false,
+                        otherwise: None,
+                        id: None,
+                    })
+                }
+                (Some(command), model::Target::File(trigger)) => {
+                    let misuse = if !matches!(
+                        command,
+                        model::Command::Delete | model::Command::Error | model::Command::Warn
+                    ) {
+                        Some((
+                            "`FILE` only works with `DELETE`, `ERROR` and `WARN`",
+                            "cover the code with `BEGIN` and `END` instead",
+                        ))
+                    } else if !at_top(parser, &lines[..index]) {
+                        Some((
+                            "`FILE` must be at the top of the file",
+                            "only comments and blank lines may precede a `FILE` annotation",
+                        ))
+                    } else {
+                        None
+                    };
+                    if let Some((message, help)) = misuse {
+                        diagnostics.push(
+                            Diagnostic::new(Code::UnexpectedAnnotation, whole(line), message)
+                                .help(help)
+                                .at(index, line),
+                        );
+                        continue;
+                    }
+                    plans.push(Plan {
+                        command,
+                        trigger,
+                        meta: cfg.meta,
+                        range: Range {
+                            begin: 0,
+                            end: lines.len().saturating_sub(1),
+                        },
+                        markers: vec![index],
+                        file: true,
+                        otherwise: None,
+                        id: None,
+                    })
+                }
+                (Some(command), model::Target::Item(trigger)) => {
+                    let syntax = parser.syntax();
+                    let braces = || item::braces(lines, index + 1, syntax);
+                    let range = match syntax.blocks {
+                        Blocks::Braces => braces().map(|end| Range { begin: index, end }),
+                        Blocks::Indent => {
+                            item::indent(lines, index + 1).map(|end| Range { begin: index, end })
+                        }
+                        Blocks::Rust => ast
+                            .get_or_insert_with(|| item::parse(lines))
+                            .as_ref()
+                            .and_then(|file| item::rust(file, lines, index))
+                            .or_else(|| braces().map(|end| Range { begin: index, end })),
+                    };
+                    let Some(range) = range else {
+                        diagnostics.push(
+                            Diagnostic::new(
+                                Code::UnresolvedItem,
+                                whole(line),
+                                "cannot find the end of the item following `ITEM`",
+                            )
+                            .help("check that the brackets of the next item are balanced")
+                            .at(index, line),
+                        );
+                        continue;
+                    };
+                    plans.push(Plan {
+                        command,
+                        trigger,
+                        meta: cfg.meta,
+                        range,
+                        markers: vec![index],
+                        file: false,
+                        otherwise: None,
+                        id: None,
+                    })
+                }
+                (None, _) => unreachable!("the command is only omitted on END and ELSE"),
+            }
+        }
+        for block in blocks {
+            let line = &lines[block.begin];
+            unpaired.push(
+                Diagnostic::new(
+                    Code::UnclosedBlock,
+                    whole(line),
+                    "`BEGIN` has no matching `END`",
+                )
+                .help("close the block with an `END` annotation")
+                .at(block.begin, line),
+            );
+        }
+        for (id, block) in labeled {
+            let line = &lines[block.begin];
+            unpaired.push(
+                Diagnostic::new(
+                    Code::UnclosedBlock,
+                    whole(line),
+                    format!("`BEGIN id={id}` has no matching `END id={id}`"),
+                )
+                .help(format!("close the block with `END id={id}`"))
+                .at(block.begin, line),
+            );
+        }
+
+        // Pairing errors are only meaningful once every annotation parsed.
+        if diagnostics.is_empty() {
+            diagnostics = unpaired;
+        }
+        if !diagnostics.is_empty() {
+            return Err(Diagnostics(diagnostics));
+        }
+        if plans.is_empty() {
+            return Ok(None);
+        }
+        plans.sort_by_key(|p| (p.range.begin, std::cmp::Reverse(p.range.end)));
+        Ok(Some(Self { plans }))
+    }
+
+    // Starts the TTLs of each plan at `since` of its annotation line.
+    pub fn stamp(
+        &mut self,
+        mut since: impl FnMut(usize) -> Result<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<()> {
+        for plan in &mut self.plans {
+            if !plan.trigger.ttls().is_empty() {
+                plan.trigger.stamp(since(plan.markers[0])?);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Plan> {
+        self.plans.iter()
+    }
+
+    // Markers that would lose their partner if `begin..=end` is deleted, e.g. the END
+    // of a labeled block interleaving with the deleted one.
+    pub fn orphans(&self, begin: usize, end: usize) -> Vec<usize> {
+        let inside = |index: &usize| begin <= *index && *index <= end;
+        let mut orphans = self
+            .plans
+            .iter()
+            .filter(|other| other.markers.iter().any(inside))
+            .flat_map(|other| other.markers.iter().filter(|index| !inside(index)))
+            .copied()
+            .collect::<Vec<_>>();
+        orphans.sort();
+        orphans.dedup();
+        orphans
+    }
+}
+
+fn neighbor(lines: &[String], index: usize) -> Range {
+    Range {
+        begin: lines
+            .prev_match(index, |line| line.trim().is_empty())
+            .map(|matched| {
+                if lines[matched].trim().is_empty() {
+                    matched + 1
+                } else {
+                    matched
+                }
+            })
+            .unwrap_or(0),
+        end: lines
+            .next_match(index, |line| line.trim().is_empty())
+            .map(|matched| {
+                if lines[matched].trim().is_empty() {
+                    matched - 1
+                } else {
+                    matched
+                }
+            })
+            .unwrap_or(lines.len() - 1),
+    }
+}
+
+// The farthest of the `count` code lines walked by `lines`, or `None` when a block
+// marker or the edge of the file comes first.
+fn neighbors<'a>(
+    parser: &config::annotation::Parser,
+    boundaries: &[usize],
+    lines: impl Iterator<Item = (usize, &'a String)>,
+    count: usize,
+) -> Option<usize> {
+    let targets = lines
+        .take_while(|(index, _)| !boundaries.contains(index))
+        .filter(|(_, line)| !parser.is_standalone(line))
+        .take(count)
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    (targets.len() == count)
+        .then(|| targets.last().copied())
+        .flatten()
+}
+
+fn too_few(line: &str, target: &str, side: &str, count: usize) -> Diagnostic {
+    Diagnostic::new(
+        Code::UnresolvedItem,
+        whole(line),
+        format!(
+            "`{target}` needs {} {side} the annotation",
+            count_lines(count)
+        ),
+    )
+    .help("`NEXT` and `PREV` stop at the edge of the file and at `BEGIN`, `END` and `ELSE` markers")
+}
+
+fn count_lines(n: usize) -> String {
+    match n {
+        1 => "1 line".to_string(),
+        _ => format!("{n} lines"),
+    }
+}
+
+// Whether only comments and blank lines come before a `FILE` annotation.
+fn at_top(parser: &config::annotation::Parser, before: &[String]) -> bool {
+    before.iter().all(|line| {
+        let line = line.trim_start();
+        line.is_empty() || line.starts_with(parser.syntax().prefix.as_str())
+    })
+}
+
+fn whole(line: &str) -> Span {
+    Span::new(line.len() - line.trim_start().len(), line.len())
+}
+
+pub(super) trait Prune {
+    fn prune(self, ctx: &context::Context) -> Result<Self>
+    where
+        Self: Sized;
+}
+
+impl Prune for Option<Plans> {
+    fn prune(self, ctx: &context::Context) -> Result<Self> {
+        match self {
+            Some(mut plans) => {
+                let mut triggered = vec![];
+                for plan in plans.plans {
+                    if ctx.is_triggered(&plan.trigger)? {
+                        triggered.push(plan);
+                    }
+                }
+                plans.plans = triggered;
+                Ok(Some(plans))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operations() {
+        testing::with_trace(|| {
+            let parser = config::annotation::Parser::new(&Default::default(), &Default::default())?;
+            struct Case {
+                lines: Vec<String>,
+                expected: Vec<Plan>,
+            }
+            let cases = [
+                Case {
+                    lines: vec![
+                        String::from("fuga"),
+                        String::from(""),
+                        String::from("hoge"),
+                        String::from("// torin DELETE BEGIN rule=foo"),
+                        String::from("some code"),
+                        String::from("// torin DELETE END"),
+                        String::from("fuga"),
+                        String::from(""),
+                        String::from("hoge"),
+                    ],
+                    expected: vec![Plan {
+                        command: model::Command::Delete,
+                        trigger: model::Trigger::Rule(model::Rule::from("foo")),
+                        meta: model::Meta::default(),
+                        range: Range { begin: 3, end: 5 },
+                        markers: vec![3, 5],
+                        file: false,
+                        otherwise: None,
+                        id: None,
+                    }],
+                },
+                Case {
+                    lines: vec![
+                        String::from("fuga"),
+                        String::from("// torin DELETE BEGIN rule=foo"),
+                        String::from("// torin DELETE END"),
+                        String::from("hoge"),
+                    ],
+                    expected: vec![Plan {
+                        command: model::Command::Delete,
+                        trigger: model::Trigger::Rule(model::Rule::from("foo")),
+                        meta: model::Meta::default(),
+                        range: Range { begin: 1, end: 2 },
+                        markers: vec![1, 2],
+                        file: false,
+                        otherwise: None,
+                        id: None,
+                    }],
+                },
+                Case {
+                    lines: vec![
+                        String::from("fuga"),
+                        String::from(""),
+                        String::from("hoge"),
+                        String::from(""),
+                        String::from("// torin DELETE NEIGHBOR rule=foo"),
+                        String::from("some code"),
+                        String::from("fuga"),
+                        String::from(""),
+                        String::from("hoge"),
+                        String::from(""),
+                    ],
+                    expected: vec![Plan {
+                        command: model::Command::Delete,
+                        trigger: model::Trigger::Rule(model::Rule::from("foo")),
+                        meta: model::Meta::default(),
+                        range: Range { begin: 4, end: 6 },
+                        markers: vec![4],
+                        file: false,
+                        otherwise: None,
+                        id: None,
+                    }],
+                },
+                Case {
+                    lines: vec![
+                        String::from("// torin DELETE BEGIN rule=foo"),
+                        String::from("a"),
+                        String::from("// torin ERROR BEGIN rule=bar"),
+                        String::from("b"),
+                        String::from("// torin ERROR END"),
+                        String::from("// torin DELETE NEIGHBOR rule=baz"),
+                        String::from("// torin DELETE END"),
+                    ],
+                    expected: vec![
+                        Plan {
+                            command: model::Command::Delete,
+                            trigger: model::Trigger::Rule(model::Rule::from("baz")),
+                            meta: model::Meta::default(),
+                            range: Range { begin: 0, end: 6 },
+                            markers: vec![5],
+                            file: false,
+                            otherwise: None,
+                            id: None,
+                        },
+                        Plan {
+                            command: model::Command::Delete,
+                            trigger: model::Trigger::Rule(model::Rule::from("foo")),
+                            meta: model::Meta::default(),
+                            range: Range { begin: 0, end: 6 },
+                            markers: vec![0, 6],
+                            file: false,
+                            otherwise: None,
+                            id: None,
+                        },
+                        Plan {
+                            command: model::Command::Error,
+                            trigger: model::Trigger::Rule(model::Rule::from("bar")),
+                            meta: model::Meta::default(),
+                            range: Range { begin: 2, end: 4 },
+                            markers: vec![2, 4],
+                            file: false,
+                            otherwise: None,
+                            id: None,
+                        },
+                    ],
+                },
+                Case {
+                    lines: vec![
+                        String::from("// torin DELETE BEGIN rule=foo id=a"),
+                        String::from("// torin DELETE BEGIN rule=bar id=b"),
+                        String::from("// torin DELETE END id=a"),
+                        String::from("// torin DELETE END id=b"),
+                    ],
+                    expected: vec![
+                        Plan {
+                            command: model::Command::Delete,
+                            trigger: model::Trigger::Rule(model::Rule::from("foo")),
+                            meta: model::Meta::default(),
+                            range: Range { begin: 0, end: 2 },
+                            markers: vec![0, 2],
+                            file: false,
+                            otherwise: None,
+                            id: Some("a".to_string()),
+                        },
+                        Plan {
+                            command: model::Command::Delete,
+                            trigger: model::Trigger::Rule(model::Rule::from("bar")),
+                            meta: model::Meta::default(),
+                            range: Range { begin: 1, end: 3 },
+                            markers: vec![1, 3],
+                            file: false,
+                            otherwise: None,
+                            id: Some("b".to_string()),
+                        },
+                    ],
+                },
+            ];
+            for case in cases {
+                let ops = Plans::parse(&parser, &case.lines)?;
+                if case.expected.is_empty() {
+                    assert!(ops.is_none(), "Expected no operations, got: {ops:?}");
+                } else {
+                    assert!(ops.is_some(), "Expected operations, got None");
+                    assert_eq!(ops.unwrap().plans, case.expected);
+                }
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        testing::with_trace(|| {
+            let parser = config::annotation::Parser::new(&Default::default(), &Default::default())?;
+            struct Case {
+                lines: Vec<&'static str>,
+                expected: Vec<(Code, usize)>,
+            }
+            let cases = [
+                Case {
+                    lines: vec!["fuga", "// torin DELETE BEGIN rule=foo", "some code"],
+                    expected: vec![(Code::UnclosedBlock, 2)],
+                },
+                Case {
+                    lines: vec![
+                        "// torin DELETE BEGIN rule=foo",
+                        "// torin DELETE END",
+                        "// torin DELETE END",
+                    ],
+                    expected: vec![(Code::UnexpectedAnnotation, 3)],
+                },
+                Case {
+                    lines: vec![
+                        "// torin DELETE BEGIN rule=foo",
+                        "// torin DELETE BEGIN rule=bar",
+                        "// torin DELETE END",
+                    ],
+                    expected: vec![(Code::UnclosedBlock, 1)],
+                },
+                Case {
+                    lines: vec![
+                        "// torin DELETE BEGIN rule=foo id=auth_v1",
+                        "// torin DELETE BEGIN rule=bar id=auth_v1",
+                        "// torin DELETE END id=auth_v1",
+                    ],
+                    expected: vec![(Code::DuplicateId, 2)],
+                },
+                Case {
+                    lines: vec![
+                        "// torin DELETE BEGIN rule=foo id=auth_v1",
+                        "// torin DELETE END id=auth_v2",
+                    ],
+                    expected: vec![(Code::MismatchedId, 2), (Code::UnclosedBlock, 1)],
+                },
+                Case {
+                    lines: vec![
+                        "// torin DELETE BEGIN rule=foo id=auth_v1",
+                        "// torin DELETE END",
+                        "// torin DELETE END id=auth_v1",
+                    ],
+                    expected: vec![(Code::MismatchedId, 2)],
+                },
+                Case {
+                    lines: vec![
+                        "// torin DELETE BEGIN rule=foo",
+                        "// torin DELETE END rul=bar",
+                        "",
+                        "// torin DELETE NEIGHBOR date=2025-13",
+                    ],
+                    expected: vec![(Code::UnknownParameter, 2), (Code::InvalidDate, 4)],
+                },
+                Case {
+                    lines: vec!["a", "// torin ELSE", "b"],
+                    expected: vec![(Code::UnexpectedAnnotation, 2)],
+                },
+                Case {
+                    lines: vec![
+                        "// torin DELETE BEGIN rule=foo",
+                        "// torin ELSE",
+                        "// torin ELSE",
+                        "// torin END",
+                    ],
+                    expected: vec![(Code::UnexpectedAnnotation, 3)],
+                },
+                Case {
+                    lines: vec![
+                        "// torin ERROR BEGIN rule=foo",
+                        "// torin ELSE",
+                        "// torin END",
+                    ],
+                    expected: vec![(Code::UnexpectedAnnotation, 2)],
+                },
+            ];
+            for case in cases {
+                let lines = case.lines.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+                let Err(Diagnostics(diagnostics)) = Plans::parse(&parser, &lines) else {
+                    panic!("Expected diagnostics for {:?}", case.lines);
+                };
+                let got = diagnostics
+                    .iter()
+                    .map(|d| {
+                        let rendered = d.render("a.rs");
+                        let lineno = rendered
+                            .split("a.rs:")
+                            .nth(1)
+                            .and_then(|rest| rest.split(':').next())
+                            .and_then(|n| n.parse().ok())
+                            .unwrap_or_default();
+                        (d.code(), lineno)
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(got, case.expected, "{:?}", case.lines);
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_line_targets() {
+        testing::with_trace(|| {
+            let parser = config::annotation::Parser::new(
+                &config::language::Syntax::line("#"),
+                &Default::default(),
+            )?;
+            struct Case {
+                lines: Vec<&'static str>,
+                expected: std::result::Result<Vec<Range>, Code>,
+            }
+            let cases = [
+                Case {
+                    lines: vec!["a = 1", "debug(a)  # torin DELETE LINE rule=foo", "b = 2"],
+                    expected: Ok(vec![Range { begin: 1, end: 1 }]),
+                },
+                Case {
+                    lines: vec!["a = 1", "# torin DELETE LINE rule=foo", "b = 2"],
+                    expected: Ok(vec![Range { begin: 1, end: 1 }]),
+                },
+                Case {
+                    lines: vec!["a = 1", "# torin DELETE NEXT rule=foo", "b = 2", "c = 3"],
+                    expected: Ok(vec![Range { begin: 1, end: 2 }]),
+                },
+                Case {
+                    lines: vec![
+                        "# torin DELETE NEXT n=3 rule=foo",
+                        "a = 1",
+                        "",
+                        "# torin ERROR NEIGHBOR rule=bar",
+                        "b = 2",
+                        "c = 3",
+                    ],
+                    expected: Ok(vec![Range { begin: 0, end: 4 }, Range { begin: 3, end: 5 }]),
+                },
+                Case {
+                    lines: vec![
+                        "a = 1",
+                        "b = 2",
+                        "# torin DELETE PREV n=2 rule=foo",
+                        "c = 3",
+                    ],
+                    expected: Ok(vec![Range { begin: 0, end: 2 }]),
+                },
+                Case {
+                    lines: vec!["# torin DELETE PREV rule=foo", "a = 1"],
+                    expected: Err(Code::UnresolvedItem),
+                },
+                Case {
+                    lines: vec!["a = 1", "# torin DELETE NEXT n=5 rule=foo", "b = 2"],
+                    expected: Err(Code::UnresolvedItem),
+                },
+                Case {
+                    lines: vec!["a = 1", "# torin DELETE NEXT rule=foo"],
+                    expected: Err(Code::UnresolvedItem),
+                },
+                Case {
+                    lines: vec![
+                        "# torin DELETE NEXT n=2 rule=foo",
+                        "a = 1",
+                        "# torin DELETE BEGIN rule=bar",
+                        "b = 2",
+                        "# torin END",
+                    ],
+                    expected: Err(Code::UnresolvedItem),
+                },
+                Case {
+                    lines: vec![
+                        "# torin DELETE NEXT rule=foo",
+                        "a = 1",
+                        "# torin DELETE BEGIN rule=bar",
+                        "b = 2",
+                        "# torin END",
+                    ],
+                    expected: Ok(vec![Range { begin: 0, end: 1 }, Range { begin: 2, end: 4 }]),
+                },
+                Case {
+                    lines: vec![
+                        "# torin DELETE BEGIN rule=bar",
+                        "a = 1",
+                        "# torin END",
+                        "b = 2",
+                        "# torin DELETE PREV n=3 rule=foo",
+                    ],
+                    expected: Err(Code::UnresolvedItem),
+                },
+                Case {
+                    lines: vec![
+                        "# torin DELETE NEXT rule=foo",
+                        "debug(a)  # torin DELETE LINE rule=bar",
+                        "b = 2",
+                    ],
+                    expected: Ok(vec![Range { begin: 0, end: 1 }, Range { begin: 1, end: 1 }]),
+                },
+                Case {
+                    lines: vec!["#!/bin/sh", "", "# torin DELETE FILE rule=foo", "a = 1"],
+                    expected: Ok(vec![Range { begin: 0, end: 3 }]),
+                },
+                Case {
+                    lines: vec!["a = 1", "# torin DELETE FILE rule=foo"],
+                    expected: Err(Code::UnexpectedAnnotation),
+                },
+                Case {
+                    lines: vec!["# torin COMMENT FILE rule=foo", "a = 1"],
+                    expected: Err(Code::UnexpectedAnnotation),
+                },
+                Case {
+                    lines: vec!["s = '# torin DELETE BEGIN rule=foo'", "url = 'a#b'"],
+                    expected: Ok(vec![]),
+                },
+            ];
+            for case in cases {
+                let lines = case.lines.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+                let ranges = Plans::parse(&parser, &lines)
+                    .map(|plans| {
+                        plans
+                            .map(|plans| plans.iter().map(|p| p.range.clone()).collect::<Vec<_>>())
+                            .unwrap_or_default()
+                    })
+                    .map_err(|d| d.0[0].code());
+                assert_eq!(ranges, case.expected, "{:?}", case.lines);
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_item_targets() {
+        testing::with_trace(|| {
+            struct Case {
+                syntax: config::language::Syntax,
+                lines: Vec<&'static str>,
+                expected: std::result::Result<Vec<Range>, Code>,
+            }
+            let cases = [
+                Case {
+                    syntax: config::language::Syntax::line("//"),
+                    lines: vec![
+                        "// torin DELETE ITEM rule=foo",
+                        "fn legacy() {",
+                        "    let s = \"}\";",
+                        "",
+                        "    s",
+                        "}",
+                        "fn kept() {}",
+                    ],
+                    expected: Ok(vec![Range { begin: 0, end: 5 }]),
+                },
+                Case {
+                    syntax: config::language::Syntax::line("//"),
+                    lines: vec![
+                        "// torin DELETE ITEM rule=foo",
+                        "if (legacy) {",
+                        "  a();",
+                        "} else {",
+                        "  b();",
+                        "}",
+                        "c();",
+                    ],
+                    expected: Ok(vec![Range { begin: 0, end: 5 }]),
+                },
+                Case {
+                    syntax: config::language::Syntax::line("#").with_blocks(Blocks::Indent),
+                    lines: vec![
+                        "# torin DELETE ITEM rule=foo",
+                        "@cache",
+                        "def legacy():",
+                        "    return call(",
+                        "        1,",
+                        "    )",
+                        "",
+                        "def kept():",
+                        "    pass",
+                    ],
+                    expected: Ok(vec![Range { begin: 0, end: 5 }]),
+                },
+                Case {
+                    syntax: config::language::Syntax::line("#").with_blocks(Blocks::Indent),
+                    lines: vec![
+                        "  # torin DELETE ITEM rule=foo",
+                        "  legacy:",
+                        "    enabled: true",
+                        "  kept: 1",
+                    ],
+                    expected: Ok(vec![Range { begin: 0, end: 2 }]),
+                },
+                Case {
+                    syntax: config::language::Syntax::line("//").with_blocks(Blocks::Rust),
+                    lines: vec![
+                        "/// Legacy entry point.",
+                        "#[deprecated]",
+                        "// torin DELETE ITEM rule=foo",
+                        "pub fn legacy() {",
+                        "    let s = '{';",
+                        "}",
+                        "fn kept() {}",
+                    ],
+                    expected: Ok(vec![Range { begin: 0, end: 5 }]),
+                },
+                Case {
+                    syntax: config::language::Syntax::line("//").with_blocks(Blocks::Rust),
+                    lines: vec![
+                        "fn kept() {",
+                        "    // torin DELETE ITEM rule=foo",
+                        "    if legacy {",
+                        "        a();",
+                        "    }",
+                        "}",
+                    ],
+                    expected: Ok(vec![Range { begin: 1, end: 4 }]),
+                },
+                Case {
+                    syntax: config::language::Syntax::line("//"),
+                    lines: vec!["// torin DELETE ITEM rule=foo", "fn broken() {", "    a"],
+                    expected: Err(Code::UnresolvedItem),
+                },
+            ];
+            for case in cases {
+                let parser = config::annotation::Parser::new(&case.syntax, &Default::default())?;
+                let lines = case.lines.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+                let got = Plans::parse(&parser, &lines)
+                    .map(|plans| {
+                        plans
+                            .map(|plans| plans.iter().map(|p| p.range.clone()).collect::<Vec<_>>())
+                            .unwrap_or_default()
+                    })
+                    .map_err(|d| d.0[0].code());
+                assert_eq!(got, case.expected, "{:?}", case.lines);
+            }
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: MPL-2.0
+use super::mode;
+use super::Status;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Summary {
+    pub changes: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    pub invalid: usize,
+    pub blocked: usize,
+    // Each rule reference as `(declaration, location)`, for `--verbose`.
+    pub references: Vec<(String, String)>,
+}
+
+impl Summary {
+    pub fn status(&self, mode: mode::Mode, deny_warnings: bool) -> Status {
+        let due = self.changes > 0 || self.errors > 0 || (deny_warnings && self.warnings > 0);
+        if self.invalid > 0 || self.blocked > 0 || (mode == mode::Mode::Check && due) {
+            Status::Failure
+        } else {
+            Status::Success
+        }
+    }
+}
+
+impl std::ops::AddAssign for Summary {
+    fn add_assign(&mut self, other: Self) {
+        self.changes += other.changes;
+        self.errors += other.errors;
+        self.warnings += other.warnings;
+        self.invalid += other.invalid;
+        self.blocked += other.blocked;
+        self.references.extend(other.references);
+    }
+}
+
+pub fn count(n: usize, noun: &str) -> String {
+    match n {
+        1 => format!("{n} {noun}"),
+        _ => format!("{n} {noun}s"),
+    }
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, {}, {}",
+            count(self.changes, "change"),
+            count(self.errors, "error"),
+            count(self.warnings, "warning")
+        )?;
+        if self.invalid > 0 {
+            write!(f, ", {}", count(self.invalid, "invalid file"))?;
+        }
+        if self.blocked > 0 {
+            write!(f, ", {}", count(self.blocked, "blocked file"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status() {
+        struct Case {
+            name: &'static str,
+            summary: Summary,
+            mode: mode::Mode,
+            deny_warnings: bool,
+            failure: bool,
+        }
+        let warned = Summary {
+            warnings: 2,
+            ..Default::default()
+        };
+        for case in [
+            Case {
+                name: "warnings pass check",
+                summary: warned.clone(),
+                mode: mode::Mode::Check,
+                deny_warnings: false,
+                failure: false,
+            },
+            Case {
+                name: "denied warnings fail check",
+                summary: warned.clone(),
+                mode: mode::Mode::Check,
+                deny_warnings: true,
+                failure: true,
+            },
+            Case {
+                name: "denied warnings pass plan",
+                summary: warned,
+                mode: mode::Mode::Plan,
+                deny_warnings: true,
+                failure: false,
+            },
+            Case {
+                name: "errors fail check",
+                summary: Summary {
+                    errors: 1,
+                    ..Default::default()
+                },
+                mode: mode::Mode::Check,
+                deny_warnings: false,
+                failure: true,
+            },
+            Case {
+                name: "blocked files fail apply",
+                summary: Summary {
+                    blocked: 1,
+                    ..Default::default()
+                },
+                mode: mode::Mode::Apply,
+                deny_warnings: false,
+                failure: true,
+            },
+            Case {
+                name: "invalid files fail apply",
+                summary: Summary {
+                    invalid: 1,
+                    ..Default::default()
+                },
+                mode: mode::Mode::Apply,
+                deny_warnings: false,
+                failure: true,
+            },
+        ] {
+            let failure = matches!(
+                case.summary.status(case.mode, case.deny_warnings),
+                Status::Failure
+            );
+            assert_eq!(failure, case.failure, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        let mut summary = Summary {
+            changes: 1,
+            warnings: 2,
+            ..Default::default()
+        };
+        assert_eq!(summary.to_string(), "1 change, 0 errors, 2 warnings");
+        summary += Summary {
+            invalid: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            summary.to_string(),
+            "1 change, 0 errors, 2 warnings, 1 invalid file"
+        );
+    }
+}
@@ -1,6 +1,4 @@
 // SPDX-License-Identifier: MPL-2.0
 mod lines;
-mod sized_split;
 
 pub use lines::*;
-pub use sized_split::*;
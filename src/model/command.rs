@@ -5,12 +5,27 @@ use crate::prelude::*;
 pub enum Command {
     Delete,
     Error,
-    // Add UnComment,
+    Uncomment,
+    Comment,
+    Unwrap,
+    Warn,
+    Require,
 }
 
 impl Command {
-    pub fn is_error(&self) -> bool {
-        matches!(self, Command::Error)
+    pub const KEYWORDS: [&str; 7] = [
+        "DELETE",
+        "ERROR",
+        "UNCOMMENT",
+        "COMMENT",
+        "UNWRAP",
+        "WARN",
+        "REQUIRE",
+    ];
+
+    // Commands that are reported or verified instead of applied.
+    pub fn is_report(&self) -> bool {
+        matches!(self, Command::Error | Command::Warn | Command::Require)
     }
 }
 
@@ -21,6 +36,11 @@ impl TryFrom<&str> for Command {
         match s {
             "DELETE" => Ok(Command::Delete),
             "ERROR" => Ok(Command::Error),
+            "UNCOMMENT" => Ok(Command::Uncomment),
+            "COMMENT" => Ok(Command::Comment),
+            "UNWRAP" => Ok(Command::Unwrap),
+            "WARN" => Ok(Command::Warn),
+            "REQUIRE" => Ok(Command::Require),
             _ => trace!("Invalid command"),
         }
     }
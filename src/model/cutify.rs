@@ -2,6 +2,8 @@
 enum Color {
     Default,
     Red,
+    Green,
+    Yellow,
 }
 
 impl Color {
@@ -9,6 +11,8 @@ impl Color {
         match self {
             Color::Default => "39",
             Color::Red => "31",
+            Color::Green => "32",
+            Color::Yellow => "33",
         }
     }
 }
@@ -26,6 +30,16 @@ impl Cutify<'_> {
         self
     }
 
+    pub fn green(mut self) -> Self {
+        self.color = Color::Green;
+        self
+    }
+
+    pub fn yellow(mut self) -> Self {
+        self.color = Color::Yellow;
+        self
+    }
+
     pub fn bold(mut self) -> Self {
         self.bold = true;
         self
@@ -97,6 +111,22 @@ mod tests {
         assert_eq!(format!("{}", "TEST".cutify().red()), "\x1b[31mTEST\x1b[0m");
     }
 
+    #[test]
+    fn test_cutify_green() {
+        assert_eq!(
+            format!("{}", "TEST".cutify().green()),
+            "\x1b[32mTEST\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_cutify_yellow() {
+        assert_eq!(
+            format!("{}", "TEST".cutify().yellow()),
+            "\x1b[33mTEST\x1b[0m"
+        );
+    }
+
     #[test]
     fn test_cutify_bold() {
         assert_eq!(
@@ -1,34 +1,74 @@
 // SPDX-License-Identifier: MPL-2.0
-use chrono::Datelike;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
 
 use crate::prelude::*;
 
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub const DATE_HELP: &str =
+    "dates are written as `YYYY-MM-DD`, optionally with a time such as `T09:00+09:00`";
+
+// A calendar date with an optional time of day and UTC offset. Dates without an
+// offset are read in the project time zone.
+#[derive(Debug, Clone)]
 pub struct Date {
-    year: i32,
-    month: u32,
-    day: u32,
+    date: NaiveDate,
+    time: Option<NaiveTime>,
+    offset: Option<FixedOffset>,
 }
 
 impl std::fmt::Display for Date {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}-{:02}-{:02}", self.year, self.month, self.day)
+        write!(f, "{}", self.date.format("%Y-%m-%d"))?;
+        if let Some(time) = self.time {
+            write!(f, "T{}", time.format("%H:%M"))?;
+        }
+        if let Some(offset) = self.offset {
+            write!(f, "{offset}")?;
+        }
+        Ok(())
+    }
+}
+
+// Compares dates as if they were all read in UTC, so `T09:00+09:00` equals `T00:00Z`.
+impl PartialEq for Date {
+    fn eq(&self, other: &Self) -> bool {
+        self.instant(&Zone::Utc) == other.instant(&Zone::Utc)
+    }
+}
+
+impl PartialOrd for Date {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.instant(&Zone::Utc)
+            .partial_cmp(&other.instant(&Zone::Utc))
     }
 }
 
 impl Date {
+    #[cfg(test)]
     pub fn new(year: i32, month: u32, day: u32) -> Self {
-        Date { year, month, day }
+        Date {
+            date: NaiveDate::from_ymd_opt(year, month, day).expect("Invalid date"),
+            time: None,
+            offset: None,
+        }
     }
 
-    pub fn now() -> Self {
-        let now = chrono::Local::now();
-        Self::new(now.year(), now.month(), now.day())
+    // Whether the date names a fixed instant rather than one read in the project time zone.
+    pub fn has_offset(&self) -> bool {
+        self.offset.is_some()
+    }
+
+    // The moment the date starts, reading it in `zone` when it has no offset.
+    pub fn instant(&self, zone: &Zone) -> DateTime<Utc> {
+        let local = self.date.and_time(self.time.unwrap_or_default());
+        match self.offset {
+            Some(offset) => (local - offset).and_utc(),
+            None => zone.resolve(local),
+        }
     }
 
     #[cfg(test)]
     pub fn mock(year: i32, month: u32, day: u32) -> Self {
-        Date { year, month, day }
+        Self::new(year, month, day)
     }
 }
 
@@ -43,13 +83,90 @@ impl TryFrom<String> for Date {
 impl TryFrom<&str> for Date {
     type Error = Error;
 
+    // Accepts `YYYY-MM-DD`, optionally followed by `THH:MM[:SS]` and `Z` or `±HH:MM`.
     fn try_from(value: &str) -> Result<Self> {
-        let [year, month, day] = value.sized_split::<3>("-")?;
-        Ok(Date {
-            year: year.parse()?,
-            month: month.parse()?,
-            day: day.parse()?,
-        })
+        let (date, rest) = match value.split_once('T') {
+            Some((date, rest)) => (date, Some(rest)),
+            None => (value, None),
+        };
+        let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+            return trace!("Invalid date `{}`", value);
+        };
+        let Some(rest) = rest else {
+            return Ok(Date {
+                date,
+                time: None,
+                offset: None,
+            });
+        };
+        let (time, offset) = match rest.find(['Z', '+', '-']) {
+            Some(index) => rest.split_at(index),
+            None => (rest, ""),
+        };
+        let time = NaiveTime::parse_from_str(time, "%H:%M:%S")
+            .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"));
+        let offset = match offset {
+            "" => Ok(None),
+            "Z" => Ok(Some(
+                FixedOffset::east_opt(0).expect("UTC is a valid offset"),
+            )),
+            offset => offset.parse::<FixedOffset>().map(Some),
+        };
+        match (time, offset) {
+            (Ok(time), Ok(offset)) => Ok(Date {
+                date,
+                time: Some(time),
+                offset,
+            }),
+            _ => trace!("Invalid time `{}`", value),
+        }
+    }
+}
+
+// The time zone that dates without an offset, and "now", are read in.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Zone {
+    // The zone of the machine running torin.
+    Local,
+    // The default, so machines in different zones agree.
+    #[default]
+    Utc,
+    Named(chrono_tz::Tz),
+}
+
+impl Zone {
+    fn resolve(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        // A wall-clock time skipped by a DST change falls back to reading it in UTC.
+        let resolved = match self {
+            Zone::Local => chrono::Local
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|t| t.to_utc()),
+            Zone::Utc => Some(local.and_utc()),
+            Zone::Named(tz) => tz
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|t| t.to_utc()),
+        };
+        resolved.unwrap_or_else(|| local.and_utc())
+    }
+}
+
+impl TryFrom<&str> for Zone {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        match value {
+            "local" => Ok(Zone::Local),
+            "UTC" | "utc" => Ok(Zone::Utc),
+            name => match name.parse::<chrono_tz::Tz>() {
+                Ok(tz) => Ok(Zone::Named(tz)),
+                Err(_) => trace!(
+                    "Unknown time zone `{}`, expected an IANA name such as `Asia/Tokyo`, `UTC` or `local`",
+                    name
+                ),
+            },
+        }
     }
 }
 
@@ -65,14 +182,54 @@ mod tests {
 
     #[test]
     fn test_date_try_from_str() {
-        let date: Date = "2023-10-05".try_into().unwrap();
-        assert_eq!(date, Date::new(2023, 10, 5));
+        testing::with_trace(|| {
+            let date: Date = "2023-10-05".try_into()?;
+            assert_eq!(date, Date::new(2023, 10, 5));
+            for valid in [
+                "2026-01-01T09:00",
+                "2026-01-01T09:00:30Z",
+                "2026-01-01T09:00+09:00",
+                "2026-01-01T09:00-05:30",
+            ] {
+                assert!(Date::try_from(valid).is_ok(), "{valid}");
+            }
+            for invalid in [
+                "2025-13-45",
+                "2025-02-29",
+                "2025-1x-01",
+                "2026-01-01T25:00",
+                "2026-01-01T09:00+9",
+                "2026-01-01T",
+            ] {
+                assert!(Date::try_from(invalid).is_err(), "{invalid}");
+            }
+            Ok(())
+        });
     }
 
     #[test]
-    fn test_date_now() {
-        let now = Date::now();
-        assert!(now.year > 2000); // Assuming the test is run after the year 2000
+    fn test_date_instant() {
+        testing::with_trace(|| {
+            let utc = |s: &str| -> Result<DateTime<Utc>> { Ok(s.parse()?) };
+            let tokyo = Zone::try_from("Asia/Tokyo")?;
+            let date = Date::try_from("2026-01-01")?;
+            assert_eq!(date.instant(&Zone::Utc), utc("2026-01-01T00:00:00Z")?);
+            assert_eq!(date.instant(&tokyo), utc("2025-12-31T15:00:00Z")?);
+            let date = Date::try_from("2026-01-01T09:00+09:00")?;
+            assert_eq!(date.to_string(), "2026-01-01T09:00+09:00");
+            assert_eq!(date.instant(&Zone::Utc), utc("2026-01-01T00:00:00Z")?);
+            assert_eq!(date.instant(&tokyo), utc("2026-01-01T00:00:00Z")?);
+            let same = Date::try_from("2026-01-01T00:00Z")?;
+            assert_eq!(date, same);
+            assert_eq!(date.partial_cmp(&same), Some(std::cmp::Ordering::Equal));
+            // Offset-less dates compare as UTC, not as the wall clock of another zone.
+            let local = Date::try_from("2026-01-01T09:00")?;
+            assert_ne!(local, date);
+            assert_eq!(local.partial_cmp(&date), Some(std::cmp::Ordering::Greater));
+            assert_eq!(Zone::default(), Zone::Utc);
+            assert!(Zone::try_from("Mars/Olympus").is_err());
+            Ok(())
+        });
     }
 
     #[test]
@@ -0,0 +1,9 @@
+// SPDX-License-Identifier: MPL-2.0
+use super::*;
+
+// The trigger and metadata shared by every annotation written with `group=<name>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group {
+    pub trigger: Trigger,
+    pub meta: Meta,
+}
@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: MPL-2.0
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Meta {
+    pub reason: Option<String>,
+    pub owner: Option<String>,
+    pub ticket: Option<String>,
+}
+
+impl Meta {
+    pub fn is_empty(&self) -> bool {
+        self.reason.is_none() && self.owner.is_none() && self.ticket.is_none()
+    }
+
+    // Fills the fields missing in `self` from `fallback`.
+    pub fn or(self, fallback: &Meta) -> Meta {
+        Meta {
+            reason: self.reason.or_else(|| fallback.reason.clone()),
+            owner: self.owner.or_else(|| fallback.owner.clone()),
+            ticket: self.ticket.or_else(|| fallback.ticket.clone()),
+        }
+    }
+}
+
+impl std::fmt::Display for Meta {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fields = [
+            ("owner", &self.owner),
+            ("ticket", &self.ticket),
+            ("reason", &self.reason),
+        ];
+        let mut sep = "";
+        for (key, value) in fields {
+            let Some(value) = value else {
+                continue;
+            };
+            if value.is_empty() || value.contains(char::is_whitespace) {
+                write!(f, "{sep}{key}={value:?}")?;
+            } else {
+                write!(f, "{sep}{key}={value}")?;
+            }
+            sep = " ";
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meta_display() {
+        assert!(Meta::default().is_empty());
+        assert_eq!(Meta::default().to_string(), "");
+        let meta = Meta {
+            reason: Some("remove after v2 migration".to_string()),
+            owner: Some("@alice".to_string()),
+            ticket: Some("PROJ-12".to_string()),
+        };
+        assert!(!meta.is_empty());
+        assert_eq!(
+            meta.to_string(),
+            r#"owner=@alice ticket=PROJ-12 reason="remove after v2 migration""#
+        );
+    }
+}
@@ -2,12 +2,20 @@
 mod command;
 pub mod cutify;
 mod date;
+mod group;
+mod meta;
 mod rule;
 mod target;
 mod trigger;
+mod ttl;
+mod version;
 
 pub use command::Command;
-pub use date::Date;
+pub use date::{Date, Zone, DATE_HELP};
+pub use group::Group;
+pub use meta::Meta;
 pub use rule::Rule;
 pub use target::Target;
-pub use trigger::Trigger;
+pub use trigger::{version_requirement, Trigger, TriggerError};
+pub use ttl::Ttl;
+pub use version::Version;
@@ -5,23 +5,62 @@ use super::Trigger;
 
 #[derive(Debug, PartialEq)]
 pub enum Target {
-    Begin(Trigger),
-    End,
+    Begin(Trigger, Option<String>),
+    End(Option<String>),
+    Else(Option<String>),
     Neighbor(Trigger),
+    Line(Trigger),
+    Next(Trigger, usize),
+    Prev(Trigger, usize),
+    Item(Trigger),
+    File(Trigger),
 }
 
 impl Target {
-    pub fn new(target_mode: &str, trigger: Option<Trigger>) -> Result<Self> {
+    pub const KEYWORDS: [&str; 9] = [
+        "BEGIN", "END", "ELSE", "NEIGHBOR", "LINE", "NEXT", "PREV", "ITEM", "FILE",
+    ];
+
+    pub fn new(
+        target_mode: &str,
+        trigger: Option<Trigger>,
+        id: Option<String>,
+        count: Option<usize>,
+    ) -> Result<Self> {
+        if id.is_some() && !matches!(target_mode, "BEGIN" | "END" | "ELSE") {
+            return trace!("`id` is only allowed for BEGIN, ELSE and END modes");
+        }
+        if count.is_some() && !matches!(target_mode, "NEXT" | "PREV") {
+            return trace!("`n` is only allowed for NEXT and PREV modes");
+        }
         match (target_mode, trigger) {
-            ("BEGIN", Some(trigger)) => Ok(Self::Begin(trigger)),
+            ("BEGIN", Some(trigger)) => Ok(Self::Begin(trigger, id)),
             ("BEGIN", None) => trace!("Trigger is required for BEGIN mode"),
 
             ("END", Some(_)) => trace!("Trigger is not allowed for END mode"),
-            ("END", None) => Ok(Self::End),
+            ("END", None) => Ok(Self::End(id)),
+
+            ("ELSE", Some(_)) => trace!("Trigger is not allowed for ELSE mode"),
+            ("ELSE", None) => Ok(Self::Else(id)),
 
             ("NEIGHBOR", Some(trigger)) => Ok(Self::Neighbor(trigger)),
             ("NEIGHBOR", None) => trace!("Trigger is required for NEIGHBOR mode"),
 
+            ("LINE", Some(trigger)) => Ok(Self::Line(trigger)),
+            ("LINE", None) => trace!("Trigger is required for LINE mode"),
+
+            ("NEXT", Some(trigger)) => Ok(Self::Next(trigger, count.unwrap_or(1))),
+            ("NEXT", None) => trace!("Trigger is required for NEXT mode"),
+
+            ("PREV", Some(trigger)) => Ok(Self::Prev(trigger, count.unwrap_or(1))),
+            ("PREV", None) => trace!("Trigger is required for PREV mode"),
+
+            ("ITEM", Some(trigger)) => Ok(Self::Item(trigger)),
+            ("ITEM", None) => trace!("Trigger is required for ITEM mode"),
+
+            ("FILE", Some(trigger)) => Ok(Self::File(trigger)),
+            ("FILE", None) => trace!("Trigger is required for FILE mode"),
+
             _ => trace!("Invalid target mode"),
         }
     }
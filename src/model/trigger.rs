@@ -5,10 +5,494 @@ use super::*;
 pub enum Trigger {
     Rule(Rule),
     Date(Date),
+    // Active from the first date until the day before the second one.
+    Window(Date, Date),
+    Version(semver::VersionReq),
+    // Active once the Cargo feature is no longer declared.
+    Feature(String),
+    // Active while Cargo.lock resolves the crate to a matching version.
+    Dep(String, semver::VersionReq),
+    // Active while the variable is set and non-empty, or set to the given value.
+    Env(String, Option<String>),
+    Ttl(Ttl),
+    All(Vec<Trigger>),
+    Any(Vec<Trigger>),
+    Not(Box<Trigger>),
+    // Always active, used by REQUIRE blocks.
+    Always,
+}
+
+// A trigger that failed to parse. `span` is a byte range into the parsed text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriggerError {
+    pub span: std::ops::Range<usize>,
+    pub message: String,
+    pub help: Option<&'static str>,
+}
+
+impl TriggerError {
+    fn new(span: std::ops::Range<usize>, message: impl Into<String>) -> Self {
+        TriggerError {
+            span,
+            message: message.into(),
+            help: None,
+        }
+    }
+
+    fn help(mut self, help: &'static str) -> Self {
+        self.help = Some(help);
+        self
+    }
+
+    fn shift(mut self, offset: usize) -> Self {
+        self.span = self.span.start + offset..self.span.end + offset;
+        self
+    }
 }
 
 impl Trigger {
     pub fn rule(rule: impl Into<Rule>) -> Self {
         Trigger::Rule(rule.into())
     }
+
+    // Parses a date, optionally with a time and offset, or a `<date>..<date>` window.
+    pub fn date(text: &str) -> std::result::Result<Self, TriggerError> {
+        let date = |text: &str, offset: usize| {
+            Date::try_from(text).map_err(|_| {
+                TriggerError::new(
+                    offset..offset + text.len(),
+                    format!("invalid date `{text}`"),
+                )
+                .help(DATE_HELP)
+            })
+        };
+        let Some((from, until)) = text.split_once("..") else {
+            return Ok(Trigger::Date(date(text, 0)?));
+        };
+        let (start, end) = (date(from, 0)?, date(until, from.len() + 2)?);
+        // Mixed endpoints would be ordered here in UTC but read in the project time zone.
+        if start.has_offset() != end.has_offset() {
+            return Err(TriggerError::new(
+                0..text.len(),
+                format!("the window `{text}` mixes a UTC offset with a local date"),
+            )
+            .help("give both ends of a window an offset, or neither"));
+        }
+        if start >= end {
+            return Err(
+                TriggerError::new(0..text.len(), format!("the window `{text}` is empty"))
+                    .help("the end date of a window must come after its start"),
+            );
+        }
+        Ok(Trigger::Window(start, end))
+    }
+
+    // Parses a semver requirement such as `>=2.0.0`. A bare version is rejected since
+    // it reads as both "exactly" and "at least".
+    pub fn version(text: &str) -> std::result::Result<Self, TriggerError> {
+        if text.starts_with(|c: char| c.is_ascii_digit()) {
+            return Err(TriggerError::new(
+                0..text.len(),
+                format!("the version requirement `{text}` has no comparator"),
+            )
+            .help(
+                "write `version>=2.0.0` for at least a version or `version==2.0.0` for exactly it",
+            ));
+        }
+        requirement(text)
+            .map(Trigger::Version)
+            .map_err(|e| e.help("requirements are written like `version>=2.0.0`"))
+    }
+
+    // Parses `<crate><requirement>` such as `tokio>=2`. Without a requirement the
+    // trigger is active while the crate is locked at all.
+    pub fn dep(text: &str) -> std::result::Result<Self, TriggerError> {
+        let split = text.find(['<', '>', '=', '~', '^']).unwrap_or(text.len());
+        let (name, rest) = text.split_at(split);
+        if name.is_empty() {
+            return Err(TriggerError::new(0..text.len(), "missing crate name")
+                .help("dependencies are written like `dep=tokio>=2`"));
+        }
+        let requirement = match rest {
+            "" => Ok(semver::VersionReq::STAR),
+            rest => requirement(rest),
+        };
+        requirement
+            .map(|requirement| Trigger::Dep(name.to_string(), requirement))
+            .map_err(|e| {
+                e.shift(text.len() - rest.len())
+                    .help("dependencies are written like `dep=tokio>=2`")
+            })
+    }
+
+    // Builds the trigger of a single `key=value` term. Spans point into `value`.
+    pub fn term(key: &str, value: &str) -> std::result::Result<Self, TriggerError> {
+        match key {
+            "rule" | "feature" | "env" if value.is_empty() => {
+                Err(TriggerError::new(0..0, format!("`{key}` needs a name")))
+            }
+            "rule" => Ok(Trigger::rule(value)),
+            "feature" => Ok(Trigger::Feature(value.to_string())),
+            "date" => Trigger::date(value),
+            "version" => Trigger::version(value),
+            "dep" => Trigger::dep(value),
+            "ttl" => Ttl::try_from(value).map(Trigger::Ttl).map_err(|_| {
+                TriggerError::new(0..value.len(), format!("invalid ttl `{value}`"))
+                    .help("write a count followed by `h`, `d` or `w`, e.g. `ttl=90d`")
+            }),
+            "env" => Ok(match value.split_once(':') {
+                Some((name, expected)) => {
+                    Trigger::Env(name.to_string(), Some(expected.to_string()))
+                }
+                None => Trigger::Env(value.to_string(), None),
+            }),
+            _ => Err(TriggerError::new(
+                0..value.len(),
+                format!(
+                    "unknown trigger key `{key}`, expected one of `{}`",
+                    KEYS.join("`, `")
+                ),
+            )),
+        }
+    }
+
+    // The single terms of the trigger, including those nested in expressions.
+    fn terms(&self) -> Vec<&Trigger> {
+        match self {
+            Trigger::All(triggers) | Trigger::Any(triggers) => {
+                triggers.iter().flat_map(Trigger::terms).collect()
+            }
+            Trigger::Not(trigger) => trigger.terms(),
+            term => vec![term],
+        }
+    }
+
+    pub fn rules(&self) -> Vec<&Rule> {
+        self.terms()
+            .into_iter()
+            .filter_map(|term| match term {
+                Trigger::Rule(rule) => Some(rule),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn ttls(&self) -> Vec<&Ttl> {
+        self.terms()
+            .into_iter()
+            .filter_map(|term| match term {
+                Trigger::Ttl(ttl) => Some(ttl),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Starts every TTL in the trigger at `since`.
+    pub fn stamp(&mut self, since: chrono::DateTime<chrono::Utc>) {
+        match self {
+            Trigger::Ttl(ttl) => ttl.stamp(since),
+            Trigger::All(triggers) | Trigger::Any(triggers) => {
+                triggers.iter_mut().for_each(|t| t.stamp(since));
+            }
+            Trigger::Not(trigger) => trigger.stamp(since),
+            _ => {}
+        }
+    }
+
+    // Parses a trigger expression such as `all(date=2026-01-01, not(rule=legacy))`.
+    pub fn parse(expr: &str) -> std::result::Result<Self, TriggerError> {
+        let mut cursor = Cursor { src: expr, pos: 0 };
+        let trigger = cursor.expr()?;
+        cursor.skip_whitespace();
+        if cursor.pos < expr.len() {
+            return Err(TriggerError::new(
+                cursor.pos..expr.len(),
+                format!("unexpected `{}` after the expression", &expr[cursor.pos..]),
+            ));
+        }
+        Ok(trigger)
+    }
+}
+
+fn requirement(text: &str) -> std::result::Result<semver::VersionReq, TriggerError> {
+    semver::VersionReq::parse(text).map_err(|e| {
+        TriggerError::new(
+            0..text.len(),
+            format!("invalid version requirement `{text}`: {e}"),
+        )
+    })
+}
+
+// Splits the requirement off `version>=2.0.0` or `version=2.0.0`. Comparators are
+// written directly after the key, unlike other parameters.
+pub fn version_requirement(text: &str) -> Option<&str> {
+    let rest = text.strip_prefix("version")?;
+    rest.strip_prefix('=')
+        .or_else(|| rest.starts_with(['<', '>', '~', '^']).then_some(rest))
+}
+
+pub const KEYS: [&str; 7] = ["rule", "date", "version", "feature", "dep", "env", "ttl"];
+
+const FUNCTIONS: [&str; 3] = ["all", "any", "not"];
+
+struct Cursor<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.pos = self.src.len() - self.rest().trim_start().len();
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        let eaten = self.rest().starts_with(c);
+        if eaten {
+            self.pos += c.len_utf8();
+        }
+        eaten
+    }
+
+    // Consumes everything up to the next `,`, `(`, `)` or whitespace.
+    fn word(&mut self) -> (usize, &'a str) {
+        self.skip_whitespace();
+        let start = self.pos;
+        let len = self
+            .rest()
+            .find(|c: char| matches!(c, ',' | '(' | ')') || c.is_whitespace())
+            .unwrap_or(self.rest().len());
+        self.pos += len;
+        (start, &self.src[start..self.pos])
+    }
+
+    fn expr(&mut self) -> std::result::Result<Trigger, TriggerError> {
+        let (start, word) = self.word();
+        if word.is_empty() {
+            return Err(TriggerError::new(
+                start..start + self.rest().chars().next().map_or(0, char::len_utf8),
+                "expected a trigger such as `rule=...`, `date=...` or `all(...)`",
+            ));
+        }
+        if let Some(requirement) = version_requirement(word) {
+            let offset = start + word.len() - requirement.len();
+            return Trigger::version(requirement).map_err(|e| e.shift(offset));
+        }
+        if let Some((key, value)) = word.split_once('=') {
+            let offset = start + key.len() + 1;
+            if !KEYS.contains(&key) {
+                return Err(TriggerError::new(
+                    start..start + word.len(),
+                    format!(
+                        "unknown trigger `{word}`, expected one of `{}`",
+                        KEYS.join("`, `")
+                    ),
+                ));
+            }
+            return Trigger::term(key, value).map_err(|e| e.shift(offset));
+        }
+        if !FUNCTIONS.contains(&word) {
+            return Err(TriggerError::new(
+                start..start + word.len(),
+                format!("unknown function `{word}`, expected one of `all`, `any` or `not`"),
+            ));
+        }
+        if !self.eat('(') {
+            return Err(TriggerError::new(
+                start..self.pos,
+                format!("expected `(` after `{word}`"),
+            ));
+        }
+        let mut args = vec![self.expr()?];
+        while self.eat(',') {
+            args.push(self.expr()?);
+        }
+        if !self.eat(')') {
+            return Err(TriggerError::new(
+                start..self.pos,
+                format!("unclosed `{word}(`, expected `,` or `)`"),
+            ));
+        }
+        match (word, args.len()) {
+            ("all", _) => Ok(Trigger::All(args)),
+            ("any", _) => Ok(Trigger::Any(args)),
+            (_, 1) => Ok(Trigger::Not(Box::new(args.remove(0)))),
+            _ => Err(TriggerError::new(
+                start..self.pos,
+                "`not` takes exactly one trigger",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stamp() {
+        let mut trigger = Trigger::parse("all(ttl=1d, not(ttl=2d), rule=a)").expect("valid");
+        assert!(trigger.ttls().iter().all(|ttl| ttl.expires().is_none()));
+        trigger.stamp(chrono::DateTime::UNIX_EPOCH);
+        assert_eq!(
+            trigger
+                .ttls()
+                .iter()
+                .map(|ttl| ttl.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "ttl=1d expires 1970-01-02T00:00Z",
+                "ttl=2d expires 1970-01-03T00:00Z"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_term() {
+        assert_eq!(Trigger::term("rule", "a"), Ok(Trigger::rule("a")));
+        let e = Trigger::term("owner", "alice").unwrap_err();
+        assert_eq!(e.span, 0..5);
+        assert!(e.message.starts_with("unknown trigger key `owner`"));
+    }
+
+    #[test]
+    fn test_parse() {
+        struct Case {
+            expr: &'static str,
+            expected: std::result::Result<Trigger, std::ops::Range<usize>>,
+        }
+        for case in [
+            Case {
+                expr: "rule=foo",
+                expected: Ok(Trigger::rule("foo")),
+            },
+            Case {
+                expr: "all(date=2026-01-01, not(rule=legacy_customers))",
+                expected: Ok(Trigger::All(vec![
+                    Trigger::Date(Date::new(2026, 1, 1)),
+                    Trigger::Not(Box::new(Trigger::rule("legacy_customers"))),
+                ])),
+            },
+            Case {
+                expr: " any( rule=a ,rule=b ) ",
+                expected: Ok(Trigger::Any(vec![Trigger::rule("a"), Trigger::rule("b")])),
+            },
+            Case {
+                expr: "date=2026-01-01..2026-02-01",
+                expected: Ok(Trigger::Window(
+                    Date::new(2026, 1, 1),
+                    Date::new(2026, 2, 1),
+                )),
+            },
+            Case {
+                expr: "all(rule=a, date=2026-13)",
+                expected: Err(17..24),
+            },
+            Case {
+                expr: "date=2026-02-01..2026-01-01",
+                expected: Err(5..27),
+            },
+            Case {
+                expr: "date=2026-01-01T09:00+09:00..2026-01-01",
+                expected: Err(5..39),
+            },
+            Case {
+                expr: "date=2026-01-01T09:00+09:00..2026-01-01T01:00Z",
+                expected: Ok(Trigger::Window(
+                    Date::try_from("2026-01-01T00:00Z").unwrap(),
+                    Date::try_from("2026-01-01T01:00+00:00").unwrap(),
+                )),
+            },
+            Case {
+                expr: "any(version>=2.0.0, version==1.4)",
+                expected: Ok(Trigger::Any(vec![
+                    Trigger::Version(semver::VersionReq::parse(">=2.0.0").unwrap()),
+                    Trigger::Version(semver::VersionReq::parse("=1.4").unwrap()),
+                ])),
+            },
+            Case {
+                expr: "any(version>=2.0.0, version=1.4)",
+                expected: Err(28..31),
+            },
+            Case {
+                expr: "dep=tokio=1.2",
+                expected: Ok(Trigger::Dep(
+                    "tokio".to_string(),
+                    semver::VersionReq::parse("=1.2").unwrap(),
+                )),
+            },
+            Case {
+                expr: "any(dep=tokio<1.20, dep=rand, feature=legacy)",
+                expected: Ok(Trigger::Any(vec![
+                    Trigger::Dep(
+                        "tokio".to_string(),
+                        semver::VersionReq::parse("<1.20").unwrap(),
+                    ),
+                    Trigger::Dep("rand".to_string(), semver::VersionReq::STAR),
+                    Trigger::Feature("legacy".to_string()),
+                ])),
+            },
+            Case {
+                expr: "all(env=RELEASE_BUILD, env=DEPLOY_TARGET:prod)",
+                expected: Ok(Trigger::All(vec![
+                    Trigger::Env("RELEASE_BUILD".to_string(), None),
+                    Trigger::Env("DEPLOY_TARGET".to_string(), Some("prod".to_string())),
+                ])),
+            },
+            Case {
+                expr: "any(ttl=90d, rule=a)",
+                expected: Ok(Trigger::Any(vec![
+                    Trigger::Ttl(Ttl::try_from("90d").unwrap()),
+                    Trigger::rule("a"),
+                ])),
+            },
+            Case {
+                expr: "not(ttl=3m)",
+                expected: Err(8..10),
+            },
+            Case {
+                expr: "dep=tokio>=x",
+                expected: Err(9..12),
+            },
+            Case {
+                expr: "dep=>=1",
+                expected: Err(4..7),
+            },
+            Case {
+                expr: "not(version<x)",
+                expected: Err(11..13),
+            },
+            Case {
+                expr: "either(rule=a)",
+                expected: Err(0..6),
+            },
+            Case {
+                expr: "not(rule=a, rule=b)",
+                expected: Err(0..19),
+            },
+            Case {
+                expr: "all(rule=a",
+                expected: Err(0..10),
+            },
+            Case {
+                expr: "all()",
+                expected: Err(4..5),
+            },
+            Case {
+                expr: "owner=me",
+                expected: Err(0..8),
+            },
+            Case {
+                expr: "rule=a)",
+                expected: Err(6..7),
+            },
+        ] {
+            let got = Trigger::parse(case.expr).map_err(|e| e.span);
+            assert_eq!(got, case.expected, "{}", case.expr);
+        }
+    }
 }
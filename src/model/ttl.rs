@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: MPL-2.0
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::prelude::*;
+
+// A lifetime counted from when the annotation line was last committed. `since` is
+// filled in per file before the trigger is evaluated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ttl {
+    text: String,
+    duration: TimeDelta,
+    since: Option<DateTime<Utc>>,
+}
+
+impl Ttl {
+    pub fn stamp(&mut self, since: DateTime<Utc>) {
+        self.since = Some(since);
+    }
+
+    pub fn expires(&self) -> Option<DateTime<Utc>> {
+        self.since.map(|since| since + self.duration)
+    }
+}
+
+impl std::fmt::Display for Ttl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ttl={}", self.text)?;
+        if let Some(expires) = self.expires() {
+            write!(f, " expires {}", expires.format("%Y-%m-%dT%H:%MZ"))?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&str> for Ttl {
+    type Error = Error;
+
+    // Accepts a count followed by `h`, `d` or `w`, e.g. `90d`.
+    fn try_from(value: &str) -> Result<Self> {
+        let split = value.len() - value.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let (count, unit) = value.split_at(split);
+        let Ok(count) = count.parse::<i64>() else {
+            return trace!("Invalid ttl `{}`", value);
+        };
+        let duration = match unit {
+            "h" => TimeDelta::try_hours(count),
+            "d" => TimeDelta::try_days(count),
+            "w" => TimeDelta::try_weeks(count),
+            _ => None,
+        };
+        match duration {
+            Some(duration) if count > 0 => Ok(Ttl {
+                text: value.to_string(),
+                duration,
+                since: None,
+            }),
+            _ => trace!("Invalid ttl `{}`", value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ttl() {
+        testing::with_trace(|| {
+            let mut ttl = Ttl::try_from("90d")?;
+            assert_eq!(ttl.expires(), None);
+            assert_eq!(ttl.to_string(), "ttl=90d");
+            ttl.stamp("2025-01-01T12:00:00Z".parse()?);
+            assert_eq!(ttl.expires(), Some("2025-04-01T12:00:00Z".parse()?));
+            assert_eq!(ttl.to_string(), "ttl=90d expires 2025-04-01T12:00Z");
+            assert_eq!(
+                Ttl::try_from("6w")?.duration,
+                TimeDelta::try_days(42).unwrap()
+            );
+            for invalid in ["90", "d", "0d", "1y", "-1d", "1.5w"] {
+                assert!(Ttl::try_from(invalid).is_err(), "{invalid}");
+            }
+            Ok(())
+        });
+    }
+}
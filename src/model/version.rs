@@ -0,0 +1,46 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::prelude::*;
+
+// The release the project is at, compared against `version` triggers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Version(semver::Version);
+
+impl Version {
+    pub fn matches(&self, requirement: &semver::VersionReq) -> bool {
+        requirement.matches(&self.0)
+    }
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl TryFrom<&str> for Version {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        match semver::Version::parse(value.trim().trim_start_matches('v')) {
+            Ok(version) => Ok(Version(version)),
+            Err(e) => trace!("Invalid version `{}`: {}", value, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version() {
+        testing::with_trace(|| {
+            let version = Version::try_from("v2.1.0")?;
+            assert_eq!(version.to_string(), "2.1.0");
+            assert!(version.matches(&semver::VersionReq::parse(">=2.0.0")?));
+            assert!(!version.matches(&semver::VersionReq::parse("<2.1.0")?));
+            assert!(Version::try_from("2.1").is_err());
+            Ok(())
+        });
+    }
+}